@@ -18,19 +18,28 @@ pub enum Change {
 
 #[derive(Debug)]
 pub enum MsgOutput {
+    Bulk(crate::tasks::bulk::Action, Vec<crate::tasks::Task>),
     Complete(Box<crate::tasks::Task>),
+    Delete(Box<crate::tasks::Task>),
+    Duplicate(Box<crate::tasks::Task>),
     Edit(Box<crate::tasks::Task>),
+    Reorder(Vec<(crate::tasks::Task, crate::tasks::Task)>),
 }
 
 macro_rules! create {
     ($sender:ident) => {{
-        let component = crate::widgets::tasks::Model::builder().launch(()).forward(
-            $sender.output_sender(),
-            |output| match output {
+        let component = crate::widgets::tasks::Model::builder()
+            .launch("agenda".to_string())
+            .forward($sender.output_sender(), |output| match output {
+                crate::widgets::task::MsgOutput::Bulk(action, tasks) => {
+                    MsgOutput::Bulk(action, tasks)
+                }
                 crate::widgets::task::MsgOutput::Complete(task) => MsgOutput::Complete(task),
+                crate::widgets::task::MsgOutput::Delete(task) => MsgOutput::Delete(task),
+                crate::widgets::task::MsgOutput::Duplicate(task) => MsgOutput::Duplicate(task),
                 crate::widgets::task::MsgOutput::Edit(task) => MsgOutput::Edit(task),
-            },
-        );
+                crate::widgets::task::MsgOutput::Reorder(changes) => MsgOutput::Reorder(changes),
+            });
         component
             .widget()
             .set_vscrollbar_policy(gtk::PolicyType::Never);
@@ -40,10 +49,12 @@ macro_rules! create {
 }
 
 macro_rules! update {
-    ($self:ident, $exp:expr, $task:ident, $get:ident, $list:ident, $date:ident) => {{
+    ($self:ident, $exp:expr, $task:ident, $period:ident, $list:ident, $date:ident) => {{
         use relm4::ComponentController as _;
 
-        let tasks = $self.$get(&$list, $date);
+        let preferences = crate::application::preferences();
+        let tasks =
+            crate::query::agenda(&$list, &preferences, crate::query::Period::$period, $date);
 
         $exp.set_expanded(!tasks.is_empty());
         $exp.set_sensitive(!tasks.is_empty());
@@ -65,100 +76,11 @@ impl Model {
         let list = crate::application::tasks();
         let date = crate::date::from_glib(widgets.calendar.date());
 
-        update!(self, widgets.past_exp, past, past_tasks, list, date);
-        update!(self, widgets.today_exp, today, today_tasks, list, date);
-        update!(
-            self,
-            widgets.tomorrow_exp,
-            tomorrow,
-            tomorrow_tasks,
-            list,
-            date
-        );
-        update!(self, widgets.week_exp, week, week_tasks, list, date);
-        update!(self, widgets.month_exp, month, month_tasks, list, date);
-    }
-
-    fn past_tasks(
-        &self,
-        list: &crate::tasks::List,
-        date: chrono::naive::NaiveDate,
-    ) -> Vec<crate::tasks::Task> {
-        self.tasks(list, None, Some(date))
-    }
-
-    fn today_tasks(
-        &self,
-        list: &crate::tasks::List,
-        date: chrono::naive::NaiveDate,
-    ) -> Vec<crate::tasks::Task> {
-        self.tasks(list, Some(date), Some(date + chrono::Duration::days(1)))
-    }
-
-    fn tomorrow_tasks(
-        &self,
-        list: &crate::tasks::List,
-        date: chrono::naive::NaiveDate,
-    ) -> Vec<crate::tasks::Task> {
-        self.tasks(
-            list,
-            Some(date + chrono::Duration::days(1)),
-            Some(date + chrono::Duration::days(2)),
-        )
-    }
-
-    fn week_tasks(
-        &self,
-        list: &crate::tasks::List,
-        date: chrono::naive::NaiveDate,
-    ) -> Vec<crate::tasks::Task> {
-        self.tasks(
-            list,
-            Some(date + chrono::Duration::days(2)),
-            Some(date + chrono::Duration::weeks(1)),
-        )
-    }
-
-    fn month_tasks(
-        &self,
-        list: &crate::tasks::List,
-        date: chrono::naive::NaiveDate,
-    ) -> Vec<crate::tasks::Task> {
-        self.tasks(
-            list,
-            Some(date + chrono::Duration::weeks(1)),
-            Some(date + chrono::Duration::weeks(4)),
-        )
-    }
-
-    fn tasks(
-        &self,
-        list: &crate::tasks::List,
-        start: Option<chrono::naive::NaiveDate>,
-        end: Option<chrono::naive::NaiveDate>,
-    ) -> Vec<crate::tasks::Task> {
-        let preferences = crate::application::preferences();
-
-        let tasks = list
-            .tasks
-            .iter()
-            .filter(|x| {
-                if let Some(due_date) = x.due_date {
-                    (preferences.done || !x.finished)
-                        && (preferences.defered
-                            || x.threshold_date.is_none()
-                            || start.is_none()
-                            || x.threshold_date.unwrap() <= start.unwrap())
-                        && (start.is_none() || due_date >= start.unwrap())
-                        && (end.is_none() || due_date < end.unwrap())
-                } else {
-                    false
-                }
-            })
-            .cloned()
-            .collect();
-
-        tasks
+        update!(self, widgets.past_exp, past, Past, list, date);
+        update!(self, widgets.today_exp, today, Today, list, date);
+        update!(self, widgets.tomorrow_exp, tomorrow, Tomorrow, list, date);
+        update!(self, widgets.week_exp, week, Week, list, date);
+        update!(self, widgets.month_exp, month, Month, list, date);
     }
 
     fn update_marks(&self, widgets: &ModelWidgets) {
@@ -1,3 +1,37 @@
+pub mod keymap {
+    use crate::application::Keymap;
+
+    static KEYMAP: std::sync::LazyLock<std::sync::RwLock<Keymap>> =
+        std::sync::LazyLock::new(|| std::sync::RwLock::new(Keymap::new()));
+
+    pub fn get() -> Keymap {
+        KEYMAP.read().expect("Unable to rlock keymap").clone()
+    }
+
+    pub fn replace(new: Keymap) {
+        let mut keymap = KEYMAP.write().expect("Unable to wlock keymap");
+
+        *keymap = new;
+    }
+}
+
+pub mod notes {
+    use crate::tasks::{Index, List};
+
+    static NOTES: std::sync::LazyLock<std::sync::RwLock<Index>> =
+        std::sync::LazyLock::new(|| std::sync::RwLock::new(Index::new()));
+
+    pub fn get() -> std::sync::RwLockReadGuard<'static, Index> {
+        NOTES.read().expect("Unable to rlock notes")
+    }
+
+    pub fn update(list: &List) {
+        let mut notes = NOTES.write().expect("Unable to wlock notes");
+
+        notes.update(list);
+    }
+}
+
 pub mod preferences {
     use crate::application::Preferences;
 
@@ -28,12 +62,6 @@ pub mod tasks {
         TASKS.read().expect("Unable to rlock tasks").clone()
     }
 
-    pub fn add(text: &str) -> Result<(), String> {
-        let mut tasks = TASKS.write().expect("Unable to wlock tasks");
-
-        (*tasks).add(text)
-    }
-
     pub fn replace(new: List) {
         let mut tasks = TASKS.write().expect("Unable to wlock tasks");
 
@@ -0,0 +1,322 @@
+const SECTION: &str = "shortcuts";
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Shortcut {
+    Add,
+    Find,
+    Refresh,
+    Undo,
+    Redo,
+    Page(u32),
+    Next,
+    Previous,
+    Complete,
+    Edit,
+    Flag,
+    Raise,
+    Lower,
+    Defer,
+    Delete,
+}
+
+impl Shortcut {
+    pub const ALL: [Self; 22] = [
+        Self::Add,
+        Self::Find,
+        Self::Refresh,
+        Self::Undo,
+        Self::Redo,
+        Self::Page(0),
+        Self::Page(1),
+        Self::Page(2),
+        Self::Page(3),
+        Self::Page(4),
+        Self::Page(5),
+        Self::Page(6),
+        Self::Page(7),
+        Self::Next,
+        Self::Previous,
+        Self::Complete,
+        Self::Edit,
+        Self::Flag,
+        Self::Raise,
+        Self::Lower,
+        Self::Defer,
+        Self::Delete,
+    ];
+
+    pub fn name(&self) -> String {
+        let name = match self {
+            Self::Add => "add",
+            Self::Find => "find",
+            Self::Refresh => "refresh",
+            Self::Undo => "undo",
+            Self::Redo => "redo",
+            Self::Page(n) => return format!("page-{}", n + 1),
+            Self::Next => "next",
+            Self::Previous => "previous",
+            Self::Complete => "complete",
+            Self::Edit => "edit",
+            Self::Flag => "flag",
+            Self::Raise => "raise",
+            Self::Lower => "lower",
+            Self::Defer => "defer",
+            Self::Delete => "delete",
+        };
+
+        name.to_string()
+    }
+
+    pub fn title(&self) -> String {
+        let title = match self {
+            Self::Add => "New task",
+            Self::Find => "Search",
+            Self::Refresh => "Refresh tasks",
+            Self::Undo => "Undo",
+            Self::Redo => "Redo",
+            Self::Page(n) => return format!("Go to page {}", n + 1),
+            Self::Next => "Next task",
+            Self::Previous => "Previous task",
+            Self::Complete => "Mark as done",
+            Self::Edit => "Edit",
+            Self::Flag => "Flag or unflag",
+            Self::Raise => "Raise priority",
+            Self::Lower => "Lower priority",
+            Self::Defer => "Defer until tomorrow",
+            Self::Delete => "Delete",
+        };
+
+        title.to_string()
+    }
+
+    pub fn is_global(&self) -> bool {
+        self.group() != "Tasks"
+    }
+
+    fn group(&self) -> &'static str {
+        match self {
+            Self::Add | Self::Find | Self::Refresh | Self::Undo | Self::Redo => "General",
+            Self::Page(_) => "Navigation",
+            _ => "Tasks",
+        }
+    }
+
+    fn defaults(&self) -> Vec<String> {
+        let defaults: &[&str] = match self {
+            Self::Add => &["<Control>N"],
+            Self::Find => &["<Control>F", "F3"],
+            Self::Refresh => &["<Control>R", "F5"],
+            Self::Undo => &["<Control>Z"],
+            Self::Redo => &["<Control><Shift>Z"],
+            Self::Page(n) => return vec![format!("<Control>{}", n + 1)],
+            Self::Next => &["j"],
+            Self::Previous => &["k"],
+            Self::Complete => &["x"],
+            Self::Edit => &["Return", "e"],
+            Self::Flag => &["f"],
+            Self::Raise => &["plus"],
+            Self::Lower => &["minus"],
+            Self::Defer => &["d"],
+            Self::Delete => &["Delete"],
+        };
+
+        defaults.iter().map(|x| x.to_string()).collect()
+    }
+}
+
+impl std::str::FromStr for Shortcut {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.name() == s)
+            .ok_or_else(|| format!("Unknown shortcut '{s}'"))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keymap {
+    bindings: std::collections::BTreeMap<Shortcut, Vec<String>>,
+}
+
+impl Keymap {
+    pub fn new() -> Self {
+        Self {
+            bindings: Shortcut::ALL.map(|x| (x, x.defaults())).into(),
+        }
+    }
+
+    pub fn load() -> Self {
+        Self::from_config(&crate::config::Config::load())
+    }
+
+    pub fn bindings(&self) -> impl Iterator<Item = (Shortcut, &String)> {
+        self.bindings
+            .iter()
+            .flat_map(|(shortcut, triggers)| triggers.iter().map(|x| (*shortcut, x)))
+    }
+
+    pub fn ui(&self) -> String {
+        let mut groups = Vec::<(&str, String)>::new();
+
+        for (shortcut, triggers) in &self.bindings {
+            if triggers.is_empty() {
+                continue;
+            }
+
+            let child = format!(
+                r#"<child><object class="GtkShortcutsShortcut"><property name="accelerator">{}</property><property name="title">{}</property></object></child>"#,
+                escape(&triggers.join(" ")),
+                escape(&shortcut.title()),
+            );
+
+            match groups
+                .iter_mut()
+                .find(|(group, _)| *group == shortcut.group())
+            {
+                Some((_, children)) => children.push_str(&child),
+                None => groups.push((shortcut.group(), child)),
+            }
+        }
+
+        let groups = groups
+            .iter()
+            .map(|(title, children)| {
+                format!(
+                    r#"<child><object class="GtkShortcutsGroup"><property name="title">{title}</property>{children}</object></child>"#
+                )
+            })
+            .collect::<String>();
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><interface><object class="GtkShortcutsWindow" id="shortcuts"><property name="modal">1</property><child><object class="GtkShortcutsSection"><property name="section-name">shortcuts</property>{groups}</object></child></object></interface>"#
+        )
+    }
+
+    fn from_config(config: &crate::config::Config) -> Self {
+        let mut configured = Vec::<(Shortcut, Vec<String>)>::new();
+
+        for (name, value) in config.section(SECTION) {
+            match name.parse::<Shortcut>() {
+                Ok(shortcut) => configured.push((
+                    shortcut,
+                    value.split_whitespace().map(str::to_string).collect(),
+                )),
+                Err(err) => log::warn!("{err}"),
+            }
+        }
+
+        let defaults = Shortcut::ALL
+            .into_iter()
+            .filter(|x| !configured.iter().any(|(shortcut, _)| shortcut == x))
+            .map(|x| (x, x.defaults()))
+            .collect::<Vec<_>>();
+
+        let mut used = std::collections::HashMap::<String, Shortcut>::new();
+        let mut bindings = std::collections::BTreeMap::new();
+
+        for (shortcut, triggers) in configured.into_iter().chain(defaults) {
+            let triggers = triggers
+                .into_iter()
+                .filter(|trigger| match used.get(&normalize(trigger)) {
+                    Some(other) => {
+                        log::warn!(
+                            "Shortcut {trigger} already bound to {}, ignored for {}",
+                            other.name(),
+                            shortcut.name()
+                        );
+                        false
+                    }
+                    None => {
+                        used.insert(normalize(trigger), shortcut);
+                        true
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            bindings.insert(shortcut, triggers);
+        }
+
+        Self { bindings }
+    }
+}
+
+fn normalize(trigger: &str) -> String {
+    let mut modifiers = Vec::new();
+    let mut key = trigger.trim();
+
+    while let Some((modifier, rest)) = key.strip_prefix('<').and_then(|x| x.split_once('>')) {
+        let modifier = match modifier.to_lowercase().as_str() {
+            "ctrl" | "control" | "primary" => "control".to_string(),
+            modifier => modifier.to_string(),
+        };
+
+        modifiers.push(modifier);
+        key = rest;
+    }
+
+    modifiers.sort();
+    modifiers.dedup();
+
+    let modifiers = modifiers
+        .iter()
+        .map(|x| format!("<{x}>"))
+        .collect::<String>();
+
+    format!("{modifiers}{}", key.to_lowercase())
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::application::keymap::*;
+
+    fn triggers(keymap: &Keymap, shortcut: Shortcut) -> Vec<&str> {
+        keymap
+            .bindings()
+            .filter(|(x, _)| *x == shortcut)
+            .map(|(_, x)| x.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn rebind() {
+        let config = crate::config::Config::parse(
+            "[shortcuts]\ncomplete = c space\nadd = <Control>T\nredo =\nsave = <Control>S\n",
+        );
+        let keymap = Keymap::from_config(&config);
+
+        assert_eq!(triggers(&keymap, Shortcut::Complete), ["c", "space"]);
+        assert_eq!(triggers(&keymap, Shortcut::Add), ["<Control>T"]);
+        assert!(triggers(&keymap, Shortcut::Redo).is_empty());
+        assert_eq!(triggers(&keymap, Shortcut::Page(0)), ["<Control>1"]);
+        assert_eq!(triggers(&Keymap::new(), Shortcut::Add), ["<Control>N"]);
+        assert_eq!(Keymap::from_config(&Default::default()), Keymap::new());
+    }
+
+    #[test]
+    fn conflicts() {
+        let config =
+            crate::config::Config::parse("[shortcuts]\nfind = x <ctrl>r\nedit = <Ctrl>F Return\n");
+        let keymap = Keymap::from_config(&config);
+
+        assert_eq!(triggers(&keymap, Shortcut::Find), ["x", "<ctrl>r"]);
+        assert_eq!(triggers(&keymap, Shortcut::Edit), ["<Ctrl>F", "Return"]);
+        assert!(triggers(&keymap, Shortcut::Complete).is_empty());
+        assert_eq!(triggers(&keymap, Shortcut::Refresh), ["F5"]);
+    }
+
+    #[test]
+    fn ui() {
+        let ui = Keymap::new().ui();
+
+        assert!(ui.contains(r#"<property name="accelerator">&lt;Control&gt;F F3</property>"#));
+        assert!(ui.contains(r#"<property name="title">Navigation</property>"#));
+    }
+}
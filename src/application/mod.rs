@@ -1,28 +1,53 @@
 mod globals;
+mod keymap;
+mod perspective;
 mod preferences;
+mod workspace;
 
+pub use globals::keymap::get as keymap;
+pub use globals::notes::get as notes;
 pub use globals::preferences::get as preferences;
 pub use globals::tasks::get as tasks;
 
-use globals::tasks::add as add_task;
-use preferences::Preferences;
+pub use keymap::{Keymap, Shortcut};
+use perspective::Perspective;
+pub use preferences::{Preferences, Theme};
+use workspace::Workspace;
 
 use gtk::prelude::*;
 use relm4::ComponentController as _;
 
 pub const NAME: &str = env!("CARGO_PKG_NAME");
 
+const ADD_HINT: &str =
+    "Create a new task +project @context tomorrow, in 3 days, every week, !A, defer until monday";
+
 #[derive(Clone, Copy, Debug)]
 #[repr(u32)]
 enum Page {
     Inbox = 0,
     Projects,
     Contexts,
+    Tags,
     Agenda,
     Flag,
     Done,
     Search,
-    Tags,
+}
+
+impl Page {
+    fn name(&self) -> &'static str {
+        match self {
+            Page::Inbox => "inbox",
+            Page::Projects => "projects",
+            Page::Contexts => "contexts",
+            Page::Agenda => "agenda",
+            Page::Flag => "flag",
+            Page::Done => "done",
+            Page::Search => "search",
+            Page::Tags => "tags",
+        }
+    }
 }
 
 impl From<u32> for Page {
@@ -47,60 +72,142 @@ impl From<Page> for u32 {
     }
 }
 
+#[derive(Clone, Copy, Debug)]
+pub enum Change {
+    Notes,
+    Tasks,
+}
+
+#[derive(Debug)]
+pub enum Io {
+    Loaded(Option<crate::tasks::List>),
+    Written(Box<Written>),
+}
+
+#[derive(Debug)]
+pub struct Written {
+    history: crate::tasks::History,
+    result: Result<Option<String>, String>,
+    write: Write,
+}
+
+#[derive(Debug)]
+enum Write {
+    Execute { close_edit: bool },
+    Redo,
+    Undo,
+}
+
 #[derive(Clone, Debug)]
 pub enum Msg {
     Adding,
     Add(String),
+    Archive,
+    Bulk(crate::tasks::bulk::Action, Vec<crate::tasks::Task>),
+    Changed(Change),
     Complete(Box<crate::tasks::Task>),
+    Delete(Box<crate::tasks::Task>),
+    DeletePerspective(String),
+    Duplicate(Box<crate::tasks::Task>),
     Edit(Box<crate::tasks::Task>),
     EditCancel,
     EditDone(Box<crate::tasks::Task>),
+    EditPerspective(String, String, String),
     Find,
+    Group(u32),
     Help,
+    Move(
+        Box<crate::tasks::Task>,
+        crate::query::Tag,
+        Vec<String>,
+        String,
+    ),
+    Page(u32),
+    Preferences,
+    Preview(String),
+    Quit,
+    Redo,
     Refresh,
+    Reload,
+    Reorder(Vec<(crate::tasks::Task, crate::tasks::Task)>),
+    SavePerspective(String),
     Search(String),
+    Sort(u32),
+    SwitchPage(u32),
+    Undo,
+    UndoCommand(usize),
+    UpdatePreferences(Box<Preferences>),
+    Workspace(u32),
 }
 
 pub struct Model {
     agenda: relm4::Controller<crate::agenda::Model>,
-    config: todo_txt::Config,
+    auto_archive: bool,
+    busy: bool,
     contexts: relm4::Controller<crate::widgets::tags::Model>,
+    css: gtk::CssProvider,
     done: relm4::Controller<crate::done::Model>,
     edit: relm4::Controller<crate::edit::Model>,
     flag: relm4::Controller<crate::flag::Model>,
+    history: crate::tasks::History,
     inbox: relm4::Controller<crate::inbox::Model>,
     logger: relm4::Controller<crate::logger::Model>,
+    notes_changed: bool,
+    perspectives: Vec<Perspective>,
+    preferences: relm4::Controller<crate::preferences::Model>,
     projects: relm4::Controller<crate::widgets::tags::Model>,
+    queue: std::collections::VecDeque<Msg>,
+    reload_scheduled: bool,
     shortcuts: gtk::ShortcutsWindow,
     search: relm4::Controller<crate::search::Model>,
     tags: relm4::Controller<crate::widgets::tags::Model>,
+    watchers: Vec<notify::RecommendedWatcher>,
+    workspace: Option<usize>,
+    workspaces: Vec<Workspace>,
 }
 
 impl Model {
     fn load_style(&self) {
-        let css = gtk::CssProvider::new();
-        css.load_from_resource(&self.stylesheet());
+        self.apply_theme();
 
         gtk::style_context_add_provider_for_display(
             &gtk::gdk::Display::default().unwrap(),
-            &css,
+            &self.css,
             0,
         );
     }
 
-    fn stylesheet(&self) -> String {
-        let mut stylesheet = "style_light.css";
+    fn apply_theme(&self) {
+        let theme = preferences().theme;
 
-        if let Ok(theme) = std::env::var("GTK_THEME") {
-            if theme.ends_with(":dark") {
-                stylesheet = "style_dark.css";
-            }
-        } else if let Some(setting) = gtk::Settings::default() {
-            if setting.is_gtk_application_prefer_dark_theme() {
-                stylesheet = "style_dark.css";
+        if let Some(settings) = gtk::Settings::default() {
+            match theme {
+                Theme::System => settings.reset_property("gtk-application-prefer-dark-theme"),
+                Theme::Light => settings.set_gtk_application_prefer_dark_theme(false),
+                Theme::Dark => settings.set_gtk_application_prefer_dark_theme(true),
             }
         }
 
+        self.css.load_from_resource(&self.stylesheet(theme));
+    }
+
+    fn stylesheet(&self, theme: Theme) -> String {
+        let dark = match theme {
+            Theme::Light => false,
+            Theme::Dark => true,
+            Theme::System => match std::env::var("GTK_THEME") {
+                Ok(theme) => theme.ends_with(":dark"),
+                Err(_) => gtk::Settings::default()
+                    .is_some_and(|x| x.is_gtk_application_prefer_dark_theme()),
+            },
+        };
+
+        let stylesheet = if dark {
+            "style_dark.css"
+        } else {
+            "style_light.css"
+        };
+
         format!("/txt/todo/effitask/{stylesheet}")
     }
 
@@ -119,16 +226,7 @@ impl Model {
         let vbox = gtk::Box::new(gtk::Orientation::Vertical, 0);
         vbox.set_homogeneous(false);
 
-        let title = match n.into() {
-            Page::Inbox => "inbox",
-            Page::Projects => "projects",
-            Page::Contexts => "contexts",
-            Page::Agenda => "agenda",
-            Page::Flag => "flag",
-            Page::Done => "done",
-            Page::Search => "search",
-            Page::Tags => "tags",
-        };
+        let title = Page::from(n).name();
 
         let image = gtk::Image::from_icon_name(title);
         image.set_icon_size(gtk::IconSize::Large);
@@ -140,64 +238,119 @@ impl Model {
         vbox
     }
 
-    fn add(&mut self, widgets: &ModelWidgets, text: &str) {
-        match add_task(text) {
-            Ok(_) => self.update_tasks(widgets),
-            Err(err) => log::error!("Unable to create task: '{err}'"),
+    fn add(&mut self, widgets: &ModelWidgets, sender: &relm4::ComponentSender<Self>, text: &str) {
+        use std::str::FromStr as _;
+
+        let line = crate::tasks::quick_add::parse(text, crate::date::today());
+
+        match crate::tasks::Task::from_str(&line) {
+            Ok(mut task) => {
+                task.create_date = Some(crate::date::today());
+
+                let command = crate::tasks::history::Command::new("Task created").add(task);
+                self.execute(widgets, sender, command, false);
+            }
+            Err(_) => log::error!("Unable to create task: '{text}'"),
         }
 
         widgets.add_popover.popdown();
     }
 
-    fn complete(&mut self, widgets: &ModelWidgets, task: &crate::tasks::Task) {
-        let id = task.id;
-        let mut list = tasks();
+    fn preview(widgets: &ModelWidgets, text: &str) {
+        let line = crate::tasks::quick_add::parse(text, crate::date::today());
 
-        if let Some(ref mut t) = list.tasks.get_mut(id) {
-            if t.finished {
-                t.uncomplete();
-            } else {
-                t.complete();
-            }
+        if line.is_empty() {
+            widgets.add_preview.set_text(ADD_HINT);
         } else {
-            return;
+            widgets.add_preview.set_text(&line);
         }
+    }
 
-        let t = list.tasks[id].clone();
+    fn archive(&mut self, widgets: &ModelWidgets, sender: &relm4::ComponentSender<Self>) {
+        let list = tasks();
+        let mut command = crate::tasks::history::Command::new("Tasks archived");
 
-        if t.finished {
-            if let Some(ref recurrence) = t.recurrence {
-                let due = if recurrence.strict && t.due_date.is_some() {
-                    t.due_date.unwrap()
-                } else {
-                    crate::date::today()
-                };
+        for task in list.tasks.iter().filter(|x| x.finished && !x.archived) {
+            let mut archived = task.clone();
+            archived.archived = true;
 
-                let mut new: crate::tasks::Task = t.clone();
-                new.uncomplete();
-                new.create_date = Some(crate::date::today());
-                new.due_date = Some(recurrence.clone() + due);
+            command = command.edit(task.clone(), archived);
+        }
 
-                if let Some(threshold_date) = t.threshold_date {
-                    new.threshold_date = Some(recurrence.clone() + threshold_date);
-                }
+        if command.is_empty() {
+            log::info!("Nothing to archive");
+            return;
+        }
 
-                list.push(new);
-            }
+        self.execute(widgets, sender, command, false);
+    }
+
+    fn bulk(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        action: &crate::tasks::bulk::Action,
+        tasks: &[crate::tasks::Task],
+    ) {
+        let command = action.command(tasks);
+
+        if command.is_empty() {
+            log::info!("Nothing to change");
+            return;
         }
 
-        match list.write() {
-            Ok(_) => {
-                if list.tasks[id].finished {
-                    log::info!("Task done");
-                } else {
-                    log::info!("Task undone");
-                }
-            }
-            Err(err) => log::error!("Unable to save tasks: {err}"),
+        self.execute(widgets, sender, command, true);
+    }
+
+    fn complete(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        task: &crate::tasks::Task,
+    ) {
+        let command = crate::tasks::history::Command::complete(task);
+
+        self.execute(widgets, sender, command, false);
+    }
+
+    fn delete(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        task: &crate::tasks::Task,
+    ) {
+        let list = tasks();
+
+        let Some(before) = list.find(task).map(|id| list.tasks[id].clone()) else {
+            log::error!("Task '{task}' modified by another program, deletion discarded");
+            self.update_tasks(widgets, sender);
+            return;
         };
 
-        self.update_tasks(widgets);
+        let command = crate::tasks::history::Command::new("Task deleted").delete(before);
+
+        self.execute(widgets, sender, command, true);
+    }
+
+    fn duplicate(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        task: &crate::tasks::Task,
+    ) {
+        let task = task.clone();
+
+        self.write(
+            widgets,
+            sender,
+            Write::Execute { close_edit: true },
+            move |history, list| {
+                let command = crate::tasks::history::Command::duplicate(&task, &list.notes_dir)?;
+                let label = command.label.clone();
+
+                history.execute(list, command).map(|_| Some(label))
+            },
+        );
     }
 
     fn edit(&mut self, task: &crate::tasks::Task) {
@@ -206,26 +359,218 @@ impl Model {
         self.edit.widget().set_visible(true);
     }
 
-    fn save(&mut self, widgets: &ModelWidgets, task: &crate::tasks::Task) {
-        let id = task.id;
-        let mut list = tasks();
+    fn save(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        task: &crate::tasks::Task,
+    ) {
+        let list = tasks();
 
-        if list.tasks.get_mut(id).is_some() {
-            list.tasks[id] = task.clone();
-        }
+        let Some(before) = list.find(task).map(|id| list.tasks[id].clone()) else {
+            log::error!("Task '{task}' modified by another program, change discarded");
+            self.update_tasks(widgets, sender);
+            return;
+        };
+
+        let command =
+            crate::tasks::history::Command::new("Task updated").edit(before, task.clone());
+
+        self.execute(widgets, sender, command, true);
+    }
+
+    fn move_to(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        task: &crate::tasks::Task,
+        tag: crate::query::Tag,
+        from: &[String],
+        name: &str,
+    ) {
+        let list = tasks();
 
-        match list.write() {
-            Ok(_) => (),
-            Err(err) => log::error!("Unable to save tasks: {err}"),
+        let Some(before) = list.find(task).map(|id| list.tasks[id].clone()) else {
+            log::error!("Task '{task}' modified by another program, change discarded");
+            self.update_tasks(widgets, sender);
+            return;
         };
 
-        log::info!("Task updated");
+        let mut after = before.clone();
+        tag.set(&mut after, from, name);
 
-        self.update_tasks(widgets);
-        self.edit.widget().set_visible(false);
+        if after.to_string() == before.to_string() {
+            return;
+        }
+
+        let command = crate::tasks::history::Command::new("Task moved").edit(before, after);
+
+        self.execute(widgets, sender, command, false);
+    }
+
+    fn reorder(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        changes: Vec<(crate::tasks::Task, crate::tasks::Task)>,
+    ) {
+        if let Some(n) = widgets.notebook.current_page() {
+            let page = self.page(n);
+            let mut preferences = preferences();
+
+            // A preference rather than a task change: undo keeps the manual sort
+            if preferences.sort(&page) != crate::query::sort::Sort::Manual {
+                log::info!("View sorted manually to keep the new order");
+                preferences
+                    .sorts
+                    .insert(page, crate::query::sort::Sort::Manual);
+                self.update_preferences(preferences);
+                self.switch_page(widgets, n);
+            }
+        }
+
+        let list = tasks();
+        let mut command = crate::tasks::history::Command::new("Tasks reordered");
+
+        for (task, after) in changes {
+            match list.find(&task) {
+                Some(id) => command = command.edit(list.tasks[id].clone(), after),
+                None => log::warn!("Task '{task}' modified by another program, order ignored"),
+            }
+        }
+
+        if command.is_empty() {
+            self.update_tasks(widgets, sender);
+            return;
+        }
+
+        self.execute(widgets, sender, command, false);
+    }
+
+    fn execute(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        command: crate::tasks::history::Command,
+        close_edit: bool,
+    ) {
+        let label = command.label.clone();
+
+        self.write(
+            widgets,
+            sender,
+            Write::Execute { close_edit },
+            move |history, list| history.execute(list, command).map(|_| Some(label)),
+        );
+    }
+
+    fn undo(&mut self, widgets: &ModelWidgets, sender: &relm4::ComponentSender<Self>) {
+        self.write(widgets, sender, Write::Undo, |history, list| {
+            history.undo(list)
+        });
+    }
+
+    fn undo_command(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        id: usize,
+    ) {
+        self.write(widgets, sender, Write::Undo, move |history, list| {
+            history.undo_command(list, id)
+        });
+    }
+
+    fn redo(&mut self, widgets: &ModelWidgets, sender: &relm4::ComponentSender<Self>) {
+        self.write(widgets, sender, Write::Redo, |history, list| {
+            history.redo(list)
+        });
+    }
+
+    fn write<F>(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        write: Write,
+        f: F,
+    ) where
+        F: FnOnce(
+                &mut crate::tasks::History,
+                &mut crate::tasks::List,
+            ) -> Result<Option<String>, String>
+            + Send
+            + 'static,
+    {
+        let mut history = std::mem::take(&mut self.history);
+
+        self.set_busy(widgets, true);
+
+        sender.spawn_oneshot_command(move || {
+            let mut list = Self::fresh_tasks();
+            let result = f(&mut history, &mut list);
+
+            Io::Written(Box::new(Written {
+                history,
+                result,
+                write,
+            }))
+        });
+    }
+
+    fn written(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        written: Written,
+    ) {
+        self.history = written.history;
+
+        match (written.write, written.result) {
+            (Write::Execute { close_edit }, Ok(Some(label))) => {
+                if let Some(id) = self.history.last() {
+                    self.logger.emit(crate::logger::Msg::Undoable(label, id));
+                }
+
+                if close_edit {
+                    self.edit.widget().set_visible(false);
+                }
+            }
+            (Write::Undo, Ok(Some(label))) => log::info!("Undone: {label}"),
+            (Write::Redo, Ok(Some(label))) => log::info!("Redone: {label}"),
+            (_, Ok(None)) => (),
+            (Write::Execute { .. }, Err(err)) => log::error!("Unable to save tasks: {err}"),
+            (Write::Undo, Err(err)) => log::error!("Unable to undo: {err}"),
+            (Write::Redo, Err(err)) => log::error!("Unable to redo: {err}"),
+        }
+
+        self.update_tasks(widgets, sender);
+    }
+
+    fn fresh_tasks() -> crate::tasks::List {
+        let mut list = tasks();
+
+        if list.has_changed() {
+            log::warn!("Tasks modified by another program, reloading them");
+            list.reload();
+        }
+
+        list
     }
 
     fn search(&self, widgets: &ModelWidgets, query: &str) {
+        widgets.save_search.set_sensitive(!query.is_empty());
+
+        match crate::query::parser::parse(query) {
+            Ok(_) => {
+                widgets.search.remove_css_class("error");
+                widgets.search.set_tooltip_text(None);
+            }
+            Err(err) => {
+                widgets.search.add_css_class("error");
+                widgets.search.set_tooltip_text(Some(err.as_str()));
+            }
+        }
+
         if query.is_empty() {
             widgets.notebook.set_current_page(Some(Page::Inbox.into()));
             self.search.widget().set_visible(false);
@@ -238,15 +583,58 @@ impl Model {
             .emit(crate::search::MsgInput::UpdateFilter(query.to_string()));
     }
 
-    fn update_tasks(&self, widgets: &ModelWidgets) {
-        let list = crate::tasks::List::from_files(&self.config.todo_file, &self.config.done_file);
-        globals::tasks::replace(list);
+    fn reload_tasks(&mut self, widgets: &ModelWidgets, sender: &relm4::ComponentSender<Self>) {
+        let auto_archive = self.auto_archive;
+        let workspace = self.workspace;
+        let workspaces = self.workspaces.clone();
+
+        self.set_busy(widgets, true);
 
-        globals::preferences::replace(crate::application::Preferences {
-            defered: widgets.defered_button.is_active(),
-            done: widgets.done_button.is_active(),
+        sender.spawn_oneshot_command(move || {
+            let list = Self::load_tasks(workspace, &workspaces, auto_archive);
+            globals::notes::update(&list);
+
+            Io::Loaded(Some(list))
+        });
+    }
+
+    fn update_tasks(&mut self, widgets: &ModelWidgets, sender: &relm4::ComponentSender<Self>) {
+        self.set_busy(widgets, true);
+
+        sender.spawn_oneshot_command(|| {
+            let mut list = tasks();
+
+            if !list.refresh() {
+                return Io::Loaded(None);
+            }
+
+            globals::notes::update(&list);
+
+            Io::Loaded(Some(list))
         });
+    }
 
+    fn loaded(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        list: Option<crate::tasks::List>,
+    ) {
+        if let Some(list) = list {
+            globals::tasks::replace(list);
+            self.update_views();
+
+            log::info!("Tasks reloaded");
+        }
+
+        self.set_busy(widgets, false);
+
+        for msg in self.queue.drain(..) {
+            sender.input(msg);
+        }
+    }
+
+    fn update_views(&self) {
         self.agenda.sender().emit(crate::agenda::MsgInput::Update);
         self.contexts
             .sender()
@@ -262,44 +650,512 @@ impl Model {
             .sender()
             .emit(crate::widgets::tags::MsgInput::Update);
 
-        log::info!("Tasks reloaded");
+        for perspective in &self.perspectives {
+            perspective.search.emit(crate::search::MsgInput::Update);
+        }
     }
 
-    fn watch(&self, sender: relm4::ComponentSender<Self>) {
+    fn update_preferences(&self, new: Preferences) {
+        let old = preferences();
+
+        globals::preferences::replace(new.clone());
+
+        if let Err(err) = new.save() {
+            log::error!("Unable to save preferences: {err}");
+        }
+
+        if old.theme != new.theme {
+            self.apply_theme();
+        }
+
+        if old.defered != new.defered
+            || old.done != new.done
+            || old.groups != new.groups
+            || old.sorts != new.sorts
+        {
+            self.update_views();
+        }
+    }
+
+    fn page(&self, n: u32) -> String {
+        match n.checked_sub(u32::from(Page::Search) + 1) {
+            Some(n) => self
+                .perspectives
+                .get(n as usize)
+                .map(|x| Perspective::page(&x.name))
+                .unwrap_or_default(),
+            None => Page::from(n).name().to_string(),
+        }
+    }
+
+    fn sort(&self, widgets: &ModelWidgets, n: u32) {
+        let Some(page) = widgets.notebook.current_page().map(|x| self.page(x)) else {
+            return;
+        };
+
+        let sort = crate::query::sort::Sort::ALL[n as usize];
+        let mut preferences = preferences();
+
+        if preferences.sort(&page) == sort {
+            return;
+        }
+
+        preferences.sorts.insert(page, sort);
+        self.update_preferences(preferences);
+    }
+
+    fn group(&self, widgets: &ModelWidgets, n: u32) {
+        let Some(page) = widgets.notebook.current_page().map(|x| self.page(x)) else {
+            return;
+        };
+
+        let group = crate::query::group::Group::ALL[n as usize];
+        let mut preferences = preferences();
+
+        if preferences.group(&page) == group {
+            return;
+        }
+
+        preferences.groups.insert(page, group);
+        self.update_preferences(preferences);
+    }
+
+    fn switch_page(&self, widgets: &ModelWidgets, n: u32) {
+        let page = self.page(n);
+        let preferences = preferences();
+
+        let sort = preferences.sort(&page);
+        let position = crate::query::sort::Sort::ALL
+            .iter()
+            .position(|x| *x == sort)
+            .unwrap_or_default();
+        widgets.sort.set_selected(position as u32);
+
+        let group = preferences.group(&page);
+        let position = crate::query::group::Group::ALL
+            .iter()
+            .position(|x| *x == group)
+            .unwrap_or_default();
+        widgets.group.set_selected(position as u32);
+    }
+
+    fn restore_state(widgets: &ModelWidgets, root: &gtk::ApplicationWindow) {
+        let preferences = preferences();
+
+        if let Some((width, height)) = preferences.size {
+            root.set_default_size(width, height);
+        }
+
+        if let Some(pane) = preferences.pane {
+            widgets.paned.set_position(pane);
+        }
+
+        if preferences.page < widgets.notebook.n_pages()
+            && preferences.page != u32::from(Page::Search)
+        {
+            widgets.notebook.set_current_page(Some(preferences.page));
+        }
+    }
+
+    fn quit(&self, widgets: &ModelWidgets, root: &gtk::ApplicationWindow) {
+        let mut preferences = preferences();
+
+        if !root.is_maximized() {
+            preferences.size = Some(root.default_size());
+        }
+
+        preferences.pane = widgets
+            .paned
+            .is_position_set()
+            .then(|| widgets.paned.position());
+        preferences.page = widgets
+            .notebook
+            .current_page()
+            .filter(|x| *x != u32::from(Page::Search))
+            .unwrap_or_default();
+
+        if let Err(err) = preferences.save() {
+            log::error!("Unable to save preferences: {err}");
+        }
+
+        relm4::main_application().quit();
+    }
+
+    fn load_perspectives(&mut self, widgets: &ModelWidgets, sender: &relm4::ComponentSender<Self>) {
+        for (name, query) in Perspective::all() {
+            self.add_perspective(widgets, sender, name, query);
+        }
+    }
+
+    fn add_perspective(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        name: String,
+        query: String,
+    ) {
+        use relm4::Component as _;
+
+        let search = crate::search::Model::builder()
+            .launch((Perspective::page(&name), query.clone()))
+            .forward(sender.input_sender(), |output| match output {
+                crate::widgets::task::MsgOutput::Bulk(action, tasks) => Msg::Bulk(action, tasks),
+                crate::widgets::task::MsgOutput::Complete(task) => Msg::Complete(task),
+                crate::widgets::task::MsgOutput::Delete(task) => Msg::Delete(task),
+                crate::widgets::task::MsgOutput::Duplicate(task) => Msg::Duplicate(task),
+                crate::widgets::task::MsgOutput::Edit(task) => Msg::Edit(task),
+                crate::widgets::task::MsgOutput::Reorder(changes) => Msg::Reorder(changes),
+            });
+        search.emit(crate::search::MsgInput::Update);
+
+        let perspective = Perspective {
+            name,
+            query,
+            search,
+        };
+
+        widgets
+            .notebook
+            .append_page(perspective.search.widget(), Some(&perspective.tab(sender)));
+
+        self.perspectives.push(perspective);
+    }
+
+    fn save_perspective(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        name: &str,
+    ) {
+        let name = name.trim();
+        let query = widgets.search.text().to_string();
+
+        if self.perspectives.iter().any(|x| x.name == name) {
+            Self::confirm_replace(widgets, sender, name, query);
+            return;
+        }
+
+        if !crate::config::Config::is_valid_key(name) {
+            log::error!("Invalid perspective name '{name}'");
+            return;
+        }
+
+        self.add_perspective(widgets, sender, name.to_string(), query);
+        self.write_perspectives();
+
+        widgets.save_search_popover.popdown();
+        widgets
+            .notebook
+            .set_current_page(Some(widgets.notebook.n_pages() - 1));
+    }
+
+    fn confirm_replace(
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        name: &str,
+        query: String,
+    ) {
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(format!("Replace perspective '{name}'?"))
+            .detail("A perspective with this name already exists, its query will be replaced.")
+            .buttons(["Cancel", "Replace"])
+            .cancel_button(0)
+            .default_button(1)
+            .build();
+
+        let window = widgets.notebook.root().and_downcast::<gtk::Window>();
+        let name = name.to_string();
+        let sender = sender.clone();
+
+        dialog.choose(
+            window.as_ref(),
+            None::<&gtk::gio::Cancellable>,
+            move |response| {
+                if response == Ok(1) {
+                    sender.input(Msg::EditPerspective(name.clone(), name, query));
+                }
+            },
+        );
+    }
+
+    fn edit_perspective(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        old: &str,
+        name: &str,
+        query: &str,
+    ) {
+        if !crate::config::Config::is_valid_key(name) {
+            log::error!("Invalid perspective name '{name}'");
+            return;
+        }
+
+        let name = name.trim();
+
+        if name != old && self.perspectives.iter().any(|x| x.name == name) {
+            log::error!("Perspective '{name}' already exists");
+            return;
+        }
+
+        if name != old {
+            let mut preferences = preferences();
+
+            if preferences.rename_page(&Perspective::page(old), &Perspective::page(name)) {
+                self.update_preferences(preferences);
+            }
+        }
+
+        let Some(perspective) = self.perspectives.iter_mut().find(|x| x.name == old) else {
+            return;
+        };
+
+        perspective
+            .search
+            .emit(crate::search::MsgInput::Page(Perspective::page(name)));
+        perspective.name = name.to_string();
+        perspective.query = query.to_string();
+        perspective
+            .search
+            .emit(crate::search::MsgInput::UpdateFilter(query.to_string()));
+
+        widgets
+            .notebook
+            .set_tab_label(perspective.search.widget(), Some(&perspective.tab(sender)));
+
+        widgets.save_search_popover.popdown();
+        self.write_perspectives();
+    }
+
+    fn delete_perspective(&mut self, widgets: &ModelWidgets, name: &str) {
+        let Some(position) = self.perspectives.iter().position(|x| x.name == name) else {
+            return;
+        };
+
+        let perspective = self.perspectives.remove(position);
+        widgets.notebook.detach_tab(perspective.search.widget());
+
+        let mut preferences = preferences();
+
+        if preferences.remove_page(&Perspective::page(name)) {
+            self.update_preferences(preferences);
+        }
+
+        self.write_perspectives();
+    }
+
+    fn write_perspectives(&self) {
+        if let Err(err) = Perspective::save(&self.perspectives) {
+            log::error!("Unable to save perspectives: {err}");
+        }
+    }
+
+    fn set_busy(&mut self, widgets: &ModelWidgets, busy: bool) {
+        self.busy = busy;
+
+        widgets.spinner.set_visible(busy);
+        widgets.spinner.set_spinning(busy);
+    }
+
+    fn load_tasks(
+        workspace: Option<usize>,
+        workspaces: &[Workspace],
+        auto_archive: bool,
+    ) -> crate::tasks::List {
+        let mut list = crate::tasks::List::new();
+        list.set_auto_archive(auto_archive);
+
+        match workspace {
+            Some(n) => {
+                let workspace = &workspaces[n];
+
+                list.load(None, &workspace.todo_file, &workspace.done_file);
+            }
+            None => {
+                for workspace in workspaces {
+                    list.load(
+                        Some(&workspace.name),
+                        &workspace.todo_file,
+                        &workspace.done_file,
+                    );
+                }
+            }
+        }
+
+        list
+    }
+
+    fn select_workspace(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: &relm4::ComponentSender<Self>,
+        n: u32,
+    ) {
+        let n = n as usize;
+
+        self.workspace = (n < self.workspaces.len()).then_some(n);
+        self.history = crate::tasks::History::new();
+        self.edit.widget().set_visible(false);
+
+        self.reload_tasks(widgets, sender);
+    }
+
+    fn changed(&mut self, sender: relm4::ComponentSender<Self>, change: Change) {
+        if matches!(change, Change::Notes) {
+            self.notes_changed = true;
+        }
+
+        if self.reload_scheduled {
+            return;
+        }
+
+        self.reload_scheduled = true;
+
+        gtk::glib::timeout_add_local_once(std::time::Duration::from_millis(500), move || {
+            sender.input(Msg::Reload);
+        });
+    }
+
+    fn reload(&mut self, widgets: &ModelWidgets, sender: &relm4::ComponentSender<Self>) {
+        self.reload_scheduled = false;
+
+        if !std::mem::take(&mut self.notes_changed) {
+            self.update_tasks(widgets, sender);
+            return;
+        }
+
+        let auto_archive = self.auto_archive;
+        let workspace = self.workspace;
+        let workspaces = self.workspaces.clone();
+
+        self.set_busy(widgets, true);
+
+        sender.spawn_oneshot_command(move || {
+            let mut list = tasks();
+            let refreshed = list.refresh();
+
+            if list.notes_changed() {
+                list = Self::load_tasks(workspace, &workspaces, auto_archive);
+            } else if !refreshed {
+                return Io::Loaded(None);
+            }
+
+            globals::notes::update(&list);
+
+            Io::Loaded(Some(list))
+        });
+    }
+
+    fn watch(&mut self, sender: relm4::ComponentSender<Self>) {
+        self.watchers.clear();
+
+        for workspace in &self.workspaces {
+            let files = [&workspace.todo_file, &workspace.done_file]
+                .map(|x| Self::canonicalize(std::path::Path::new(x)));
+            let dirs = files
+                .iter()
+                .filter_map(|x| x.parent())
+                .map(std::path::Path::to_path_buf)
+                .collect::<std::collections::BTreeSet<_>>();
+
+            let sender = sender.clone();
+            let watcher = Self::watcher(&dirs, move |path| {
+                if files.iter().any(|x| x == path) {
+                    sender.input(Msg::Changed(Change::Tasks));
+                }
+            });
+
+            self.watchers.extend(watcher);
+        }
+
+        let notes_dir = crate::tasks::Task::notes_dir();
+
+        if notes_dir.exists() {
+            let notes_dir = Self::canonicalize(&notes_dir);
+            let dirs = std::collections::BTreeSet::from([notes_dir]);
+
+            let watcher = Self::watcher(&dirs, move |_| {
+                sender.input(Msg::Changed(Change::Notes));
+            });
+
+            self.watchers.extend(watcher);
+        }
+    }
+
+    fn watcher<F>(
+        dirs: &std::collections::BTreeSet<std::path::PathBuf>,
+        callback: F,
+    ) -> Option<notify::RecommendedWatcher>
+    where
+        F: Fn(&std::path::Path) + Send + 'static,
+    {
         use notify::Watcher as _;
 
-        let mut watcher = notify::recommended_watcher(move |res| match res {
-            Ok(_) => {
-                sender.input(Msg::Refresh);
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+                Ok(event) => {
+                    if event.kind.is_access() {
+                        return;
+                    }
+
+                    for path in &event.paths {
+                        callback(path);
+                    }
+                }
+                Err(e) => log::warn!("watch error: {e:?}"),
+            })
+            .map_err(|err| log::warn!("Unable to setup hot reload: {err}"))
+            .ok()?;
+
+        for dir in dirs {
+            log::debug!("watching {} for changes", dir.display());
+
+            if let Err(err) = watcher.watch(dir, notify::RecursiveMode::NonRecursive) {
+                log::warn!("Unable to setup hot reload: {err}");
             }
-            Err(e) => log::warn!("watch error: {e:?}"),
-        })
-        .unwrap();
+        }
+
+        Some(watcher)
+    }
 
-        log::debug!("watching {} for changes", self.config.todo_file);
+    fn canonicalize(path: &std::path::Path) -> std::path::PathBuf {
+        if let Ok(path) = std::fs::canonicalize(path) {
+            return path;
+        }
+
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => std::path::Path::new("."),
+        };
+        let dir = std::fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
 
-        if let Err(err) = watcher.watch(
-            std::path::PathBuf::from(self.config.todo_file.clone()).as_path(),
-            notify::RecursiveMode::Recursive,
-        ) {
-            log::warn!("Unable to setup hot reload: {err}");
+        match path.file_name() {
+            Some(name) => dir.join(name),
+            None => dir,
         }
     }
 
     fn shortcuts(window: &gtk::ApplicationWindow, sender: relm4::ComponentSender<Self>) {
-        static SHORTCUTS: &[(&str, Msg)] = &[
-            ("<Control>A", Msg::Adding),
-            ("<Control>F", Msg::Find),
-            ("F3", Msg::Find),
-            ("<Control>R", Msg::Refresh),
-            ("F5", Msg::Refresh),
-        ];
-
         let controller = gtk::ShortcutController::new();
         controller.set_scope(gtk::ShortcutScope::Global);
 
-        for (trigger, msg) in SHORTCUTS {
-            let trigger = gtk::ShortcutTrigger::parse_string(trigger);
+        for (shortcut, trigger) in keymap().bindings() {
+            let msg = match shortcut {
+                Shortcut::Add => Msg::Adding,
+                Shortcut::Find => Msg::Find,
+                Shortcut::Refresh => Msg::Refresh,
+                Shortcut::Undo => Msg::Undo,
+                Shortcut::Redo => Msg::Redo,
+                Shortcut::Page(n) => Msg::Page(n),
+                _ => continue,
+            };
+
+            let Some(trigger) = gtk::ShortcutTrigger::parse_string(trigger) else {
+                log::warn!("Invalid shortcut '{trigger}' for {}", shortcut.name());
+                continue;
+            };
+
             let callback = gtk::CallbackAction::new(gtk::glib::clone!(
                 #[strong]
                 sender,
@@ -311,7 +1167,7 @@ impl Model {
                 }
             ));
 
-            let shortcut = gtk::Shortcut::new(trigger, Some(callback));
+            let shortcut = gtk::Shortcut::new(Some(trigger), Some(callback));
             controller.add_shortcut(shortcut);
         }
 
@@ -321,7 +1177,7 @@ impl Model {
 
 #[relm4::component(pub)]
 impl relm4::Component for Model {
-    type CommandOutput = ();
+    type CommandOutput = Io;
     type Init = todo_txt::Config;
     type Input = Msg;
     type Output = ();
@@ -331,97 +1187,192 @@ impl relm4::Component for Model {
         root: Self::Root,
         sender: relm4::ComponentSender<Self>,
     ) -> relm4::ComponentParts<Self> {
-        let logger = crate::logger::Model::builder().launch(()).detach();
+        globals::keymap::replace(Keymap::load());
+
+        let logger =
+            crate::logger::Model::builder()
+                .launch(())
+                .forward(sender.input_sender(), |output| match output {
+                    crate::logger::MsgOutput::Undo(id) => Msg::UndoCommand(id),
+                });
 
         let agenda = crate::agenda::Model::builder()
             .launch(crate::date::today())
             .forward(sender.input_sender(), |output| match output {
+                crate::agenda::MsgOutput::Bulk(action, tasks) => Msg::Bulk(action, tasks),
                 crate::agenda::MsgOutput::Complete(task) => Msg::Complete(task),
+                crate::agenda::MsgOutput::Delete(task) => Msg::Delete(task),
+                crate::agenda::MsgOutput::Duplicate(task) => Msg::Duplicate(task),
                 crate::agenda::MsgOutput::Edit(task) => Msg::Edit(task),
+                crate::agenda::MsgOutput::Reorder(changes) => Msg::Reorder(changes),
             });
 
         let contexts = crate::widgets::tags::Model::builder()
-            .launch(crate::widgets::tags::Type::Contexts)
+            .launch(crate::query::Tag::Contexts)
             .forward(sender.input_sender(), |output| match output {
+                crate::widgets::tags::MsgOutput::Bulk(action, tasks) => Msg::Bulk(action, tasks),
                 crate::widgets::tags::MsgOutput::Complete(task) => Msg::Complete(task),
+                crate::widgets::tags::MsgOutput::Delete(task) => Msg::Delete(task),
+                crate::widgets::tags::MsgOutput::Duplicate(task) => Msg::Duplicate(task),
                 crate::widgets::tags::MsgOutput::Edit(task) => Msg::Edit(task),
+                crate::widgets::tags::MsgOutput::Move(task, tag, from, filter) => {
+                    Msg::Move(task, tag, from, filter)
+                }
+                crate::widgets::tags::MsgOutput::Reorder(changes) => Msg::Reorder(changes),
             });
 
         let done =
             crate::done::Model::builder()
                 .launch(())
                 .forward(sender.input_sender(), |output| match output {
+                    crate::widgets::task::MsgOutput::Bulk(action, tasks) => {
+                        Msg::Bulk(action, tasks)
+                    }
                     crate::widgets::task::MsgOutput::Complete(task) => Msg::Complete(task),
+                    crate::widgets::task::MsgOutput::Delete(task) => Msg::Delete(task),
+                    crate::widgets::task::MsgOutput::Duplicate(task) => Msg::Duplicate(task),
                     crate::widgets::task::MsgOutput::Edit(task) => Msg::Edit(task),
+                    crate::widgets::task::MsgOutput::Reorder(changes) => Msg::Reorder(changes),
                 });
 
         let edit = crate::edit::Model::builder()
             .launch(crate::tasks::Task::new())
             .forward(sender.input_sender(), |output| match output {
                 crate::edit::MsgOutput::Cancel => Msg::EditCancel,
+                crate::edit::MsgOutput::Delete(task) => Msg::Delete(task),
                 crate::edit::MsgOutput::Done(task) => Msg::EditDone(task),
+                crate::edit::MsgOutput::Duplicate(task) => Msg::Duplicate(task),
             });
 
         let flag =
             crate::flag::Model::builder()
                 .launch(())
                 .forward(sender.input_sender(), |output| match output {
+                    crate::widgets::task::MsgOutput::Bulk(action, tasks) => {
+                        Msg::Bulk(action, tasks)
+                    }
                     crate::widgets::task::MsgOutput::Complete(task) => Msg::Complete(task),
+                    crate::widgets::task::MsgOutput::Delete(task) => Msg::Delete(task),
+                    crate::widgets::task::MsgOutput::Duplicate(task) => Msg::Duplicate(task),
                     crate::widgets::task::MsgOutput::Edit(task) => Msg::Edit(task),
+                    crate::widgets::task::MsgOutput::Reorder(changes) => Msg::Reorder(changes),
                 });
 
         let inbox =
             crate::inbox::Model::builder()
                 .launch(())
                 .forward(sender.input_sender(), |output| match output {
+                    crate::widgets::task::MsgOutput::Bulk(action, tasks) => {
+                        Msg::Bulk(action, tasks)
+                    }
                     crate::widgets::task::MsgOutput::Complete(task) => Msg::Complete(task),
+                    crate::widgets::task::MsgOutput::Delete(task) => Msg::Delete(task),
+                    crate::widgets::task::MsgOutput::Duplicate(task) => Msg::Duplicate(task),
                     crate::widgets::task::MsgOutput::Edit(task) => Msg::Edit(task),
+                    crate::widgets::task::MsgOutput::Reorder(changes) => Msg::Reorder(changes),
                 });
 
         let projects = crate::widgets::tags::Model::builder()
-            .launch(crate::widgets::tags::Type::Projects)
+            .launch(crate::query::Tag::Projects)
             .forward(sender.input_sender(), |output| match output {
+                crate::widgets::tags::MsgOutput::Bulk(action, tasks) => Msg::Bulk(action, tasks),
                 crate::widgets::tags::MsgOutput::Complete(task) => Msg::Complete(task),
+                crate::widgets::tags::MsgOutput::Delete(task) => Msg::Delete(task),
+                crate::widgets::tags::MsgOutput::Duplicate(task) => Msg::Duplicate(task),
                 crate::widgets::tags::MsgOutput::Edit(task) => Msg::Edit(task),
+                crate::widgets::tags::MsgOutput::Move(task, tag, from, filter) => {
+                    Msg::Move(task, tag, from, filter)
+                }
+                crate::widgets::tags::MsgOutput::Reorder(changes) => Msg::Reorder(changes),
             });
 
         let search = crate::search::Model::builder()
-            .launch(String::new())
+            .launch((Page::Search.name().to_string(), String::new()))
             .forward(sender.input_sender(), |output| match output {
+                crate::widgets::task::MsgOutput::Bulk(action, tasks) => Msg::Bulk(action, tasks),
                 crate::widgets::task::MsgOutput::Complete(task) => Msg::Complete(task),
+                crate::widgets::task::MsgOutput::Delete(task) => Msg::Delete(task),
+                crate::widgets::task::MsgOutput::Duplicate(task) => Msg::Duplicate(task),
                 crate::widgets::task::MsgOutput::Edit(task) => Msg::Edit(task),
+                crate::widgets::task::MsgOutput::Reorder(changes) => Msg::Reorder(changes),
             });
 
         let tags = crate::widgets::tags::Model::builder()
-            .launch(crate::widgets::tags::Type::Hashtags)
+            .launch(crate::query::Tag::Hashtags)
             .forward(sender.input_sender(), |output| match output {
+                crate::widgets::tags::MsgOutput::Bulk(action, tasks) => Msg::Bulk(action, tasks),
                 crate::widgets::tags::MsgOutput::Complete(task) => Msg::Complete(task),
+                crate::widgets::tags::MsgOutput::Delete(task) => Msg::Delete(task),
+                crate::widgets::tags::MsgOutput::Duplicate(task) => Msg::Duplicate(task),
                 crate::widgets::tags::MsgOutput::Edit(task) => Msg::Edit(task),
+                crate::widgets::tags::MsgOutput::Move(task, tag, from, filter) => {
+                    Msg::Move(task, tag, from, filter)
+                }
+                crate::widgets::tags::MsgOutput::Reorder(changes) => Msg::Reorder(changes),
+            });
+
+        let preferences = Preferences::load();
+        globals::preferences::replace(preferences.clone());
+
+        let preferences = crate::preferences::Model::builder()
+            .transient_for(&root)
+            .launch(preferences)
+            .forward(sender.input_sender(), |output| match output {
+                crate::preferences::MsgOutput::Changed(preferences) => {
+                    Msg::UpdatePreferences(preferences)
+                }
             });
 
-        let builder = gtk::Builder::from_resource("/txt/todo/effitask/shortcuts.ui");
+        let builder = gtk::Builder::from_string(&keymap().ui());
         let shortcuts = builder.object("shortcuts").unwrap();
 
-        let model = Self {
+        let workspaces = Workspace::all(&init);
+        let mut workspace_names = workspaces
+            .iter()
+            .map(|x| x.name.as_str())
+            .collect::<Vec<_>>();
+        workspace_names.push("All lists");
+
+        let mut model = Self {
             agenda,
-            config: init,
+            auto_archive: init.auto_archive,
+            busy: false,
             contexts,
+            css: gtk::CssProvider::new(),
             done,
             edit,
             flag,
+            history: crate::tasks::History::new(),
             inbox,
             logger,
+            notes_changed: false,
+            perspectives: Vec::new(),
+            preferences,
             projects,
+            queue: std::collections::VecDeque::new(),
+            reload_scheduled: false,
             search,
             shortcuts,
             tags,
+            watchers: Vec::new(),
+            workspace: Some(0),
+            workspaces: workspaces.clone(),
         };
 
+        let groups = crate::query::group::Group::ALL.map(|x| x.label());
+        let sorts = crate::query::sort::Sort::ALL.map(|x| x.label());
+
         let widgets = view_output!();
 
         model.load_style();
         model.add_tab_widgets(&widgets.notebook);
-        model.update_tasks(&widgets);
+        model.load_perspectives(&widgets, &sender);
+        Self::restore_state(&widgets, &root);
+        model.switch_page(
+            &widgets,
+            widgets.notebook.current_page().unwrap_or_default(),
+        );
+        model.reload_tasks(&widgets, &sender);
         model.search.widget().set_visible(false);
         model.watch(sender.clone());
 
@@ -434,22 +1385,86 @@ impl relm4::Component for Model {
         &mut self,
         widgets: &mut Self::Widgets,
         msg: Self::Input,
-        _: relm4::ComponentSender<Self>,
-        _: &Self::Root,
+        sender: relm4::ComponentSender<Self>,
+        root: &Self::Root,
     ) {
+        let io = matches!(
+            msg,
+            Msg::Add(_)
+                | Msg::Archive
+                | Msg::Bulk(..)
+                | Msg::Complete(_)
+                | Msg::Delete(_)
+                | Msg::Duplicate(_)
+                | Msg::EditDone(_)
+                | Msg::Move(..)
+                | Msg::Redo
+                | Msg::Refresh
+                | Msg::Reload
+                | Msg::Reorder(_)
+                | Msg::Undo
+                | Msg::UndoCommand(_)
+                | Msg::Workspace(_)
+        );
+
+        if io && self.busy {
+            self.queue.push_back(msg);
+            return;
+        }
+
         match msg {
-            Msg::Add(task) => self.add(widgets, &task),
+            Msg::Add(task) => self.add(widgets, &sender, &task),
             Msg::Adding => widgets.add_popover.popup(),
-            Msg::Complete(task) => self.complete(widgets, &task),
+            Msg::Archive => self.archive(widgets, &sender),
+            Msg::Bulk(action, tasks) => self.bulk(widgets, &sender, &action, &tasks),
+            Msg::Changed(change) => self.changed(sender, change),
+            Msg::Complete(task) => self.complete(widgets, &sender, &task),
+            Msg::Delete(task) => self.delete(widgets, &sender, &task),
+            Msg::DeletePerspective(name) => self.delete_perspective(widgets, &name),
+            Msg::Duplicate(task) => self.duplicate(widgets, &sender, &task),
             Msg::EditCancel => self.edit.widget().set_visible(false),
-            Msg::EditDone(task) => self.save(widgets, &task),
+            Msg::EditDone(task) => self.save(widgets, &sender, &task),
+            Msg::EditPerspective(old, name, query) => {
+                self.edit_perspective(widgets, &sender, &old, &name, &query)
+            }
             Msg::Edit(task) => self.edit(&task),
             Msg::Find => {
                 widgets.search.grab_focus();
             }
+            Msg::Group(n) => self.group(widgets, n),
             Msg::Help => self.shortcuts.present(),
-            Msg::Refresh => self.update_tasks(widgets),
+            Msg::Move(task, tag, from, name) => {
+                self.move_to(widgets, &sender, &task, tag, &from, &name)
+            }
+            Msg::Page(n) => widgets.notebook.set_current_page(Some(n)),
+            Msg::Preferences => self.preferences.widget().present(),
+            Msg::Preview(text) => Self::preview(widgets, &text),
+            Msg::Quit => self.quit(widgets, root),
+            Msg::Redo => self.redo(widgets, &sender),
+            Msg::Refresh => self.reload_tasks(widgets, &sender),
+            Msg::Reload => self.reload(widgets, &sender),
+            Msg::Reorder(changes) => self.reorder(widgets, &sender, changes),
+            Msg::SavePerspective(name) => self.save_perspective(widgets, &sender, &name),
             Msg::Search(query) => self.search(widgets, &query),
+            Msg::Sort(n) => self.sort(widgets, n),
+            Msg::SwitchPage(n) => self.switch_page(widgets, n),
+            Msg::Undo => self.undo(widgets, &sender),
+            Msg::UndoCommand(id) => self.undo_command(widgets, &sender, id),
+            Msg::UpdatePreferences(preferences) => self.update_preferences(*preferences),
+            Msg::Workspace(n) => self.select_workspace(widgets, &sender, n),
+        }
+    }
+
+    fn update_cmd_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        msg: Self::CommandOutput,
+        sender: relm4::ComponentSender<Self>,
+        _: &Self::Root,
+    ) {
+        match msg {
+            Io::Loaded(list) => self.loaded(widgets, &sender, list),
+            Io::Written(written) => self.written(widgets, &sender, *written),
         }
     }
 
@@ -461,6 +1476,14 @@ impl relm4::Component for Model {
                 gtk::HeaderBar {
                     set_title_widget: Some(&gtk::Label::new(NAME.into())),
 
+                    pack_start = &gtk::DropDown::from_strings(&workspace_names) {
+                        set_tooltip_text: "Workspace".into(),
+                        set_visible: workspaces.len() > 1,
+
+                        connect_selected_notify[sender] => move |this| {
+                            sender.input(Msg::Workspace(this.selected()));
+                        },
+                    },
                     pack_start = &gtk::Button {
                         set_icon_name: "view-refresh",
                         set_tooltip_text: "Refresh".into(),
@@ -480,34 +1503,31 @@ impl relm4::Component for Model {
                                     connect_activate[sender] => move |this| {
                                         sender.input(Msg::Add(this.text().to_string()));
                                         this.set_text("");
-                                    }
+                                    },
+                                    connect_changed[sender] => move |this| {
+                                        sender.input(Msg::Preview(this.text().to_string()));
+                                    },
                                 },
+                                #[name = "add_preview"]
                                 gtk::Label {
-                                    set_text: "Create a new task +project @context due:2042-01-01",
+                                    set_text: ADD_HINT,
+                                    set_xalign: 0.,
                                 },
                             },
                         },
                     },
-                    pack_start = &gtk::MenuButton {
+                    pack_start = &gtk::Button {
+                        set_icon_name: "edit-clear-all",
+                        set_tooltip_text: "Archive done tasks".into(),
+                        set_visible: !model.auto_archive,
+
+                        connect_clicked => Msg::Archive,
+                    },
+                    pack_start = &gtk::Button {
                         set_icon_name: "preferences-system",
                         set_tooltip_text: "Preferences".into(),
-                        #[wrap(Some)]
-                        set_popover = &gtk::Popover {
-                            gtk::Box {
-                                set_orientation: gtk::Orientation::Vertical,
-                                #[name = "defered_button"]
-                                gtk::CheckButton {
-                                    set_label: Some("Display defered tasks"),
 
-                                    connect_toggled => Msg::Refresh,
-                                },
-                                #[name = "done_button"]
-                                gtk::CheckButton {
-                                    set_label: Some("Display done tasks"),
-                                    connect_toggled => Msg::Refresh,
-                                },
-                            },
-                        },
+                        connect_clicked => Msg::Preferences,
                     },
                     pack_start = &gtk::Button {
                         set_icon_name: "help-about",
@@ -517,6 +1537,45 @@ impl relm4::Component for Model {
                     },
 
                     pack_end = model.logger.widget(),
+                    #[name = "spinner"]
+                    pack_end = &gtk::Spinner {
+                        set_tooltip_text: "Syncing tasks".into(),
+                        set_visible: false,
+                    },
+                    #[name = "save_search"]
+                    pack_end = &gtk::MenuButton {
+                        set_icon_name: "document-save",
+                        set_sensitive: false,
+                        set_tooltip_text: "Save search".into(),
+                        #[wrap(Some)]
+                        #[name = "save_search_popover"]
+                        set_popover = &gtk::Popover {
+                            gtk::Entry {
+                                set_placeholder_text: Some("Perspective name"),
+
+                                connect_activate[sender] => move |this| {
+                                    sender.input(Msg::SavePerspective(this.text().to_string()));
+                                    this.set_text("");
+                                }
+                            },
+                        },
+                    },
+                    #[name = "sort"]
+                    pack_end = &gtk::DropDown::from_strings(&sorts) {
+                        set_tooltip_text: "Sort by".into(),
+
+                        connect_selected_notify[sender] => move |this| {
+                            sender.input(Msg::Sort(this.selected()));
+                        },
+                    },
+                    #[name = "group"]
+                    pack_end = &gtk::DropDown::from_strings(&groups) {
+                        set_tooltip_text: "Group by".into(),
+
+                        connect_selected_notify[sender] => move |this| {
+                            sender.input(Msg::Group(this.selected()));
+                        },
+                    },
                     #[name = "search"]
                     pack_end = &gtk::SearchEntry {
                         connect_search_changed[sender] => move |this| {
@@ -524,6 +1583,7 @@ impl relm4::Component for Model {
                         },
                     },
                 },
+                #[name = "paned"]
                 gtk::Paned {
                     set_hexpand: true,
                     set_vexpand: true,
@@ -535,6 +1595,10 @@ impl relm4::Component for Model {
                     set_start_child = &gtk::Notebook {
                         set_tab_pos: gtk::PositionType::Left,
 
+                        connect_switch_page[sender] => move |_, _, n| {
+                            sender.input(Msg::SwitchPage(n));
+                        },
+
                         append_page: (model.inbox.widget(), None::<&gtk::Label>),
                         append_page: (model.projects.widget(), None::<&gtk::Label>),
                         append_page: (model.contexts.widget(), None::<&gtk::Label>),
@@ -548,8 +1612,8 @@ impl relm4::Component for Model {
                     set_end_child = model.edit.widget(),
                 },
             },
-            connect_close_request => move |_| {
-                relm4::main_application().quit();
+            connect_close_request[sender] => move |_| {
+                sender.input(Msg::Quit);
                 gtk::glib::Propagation::Stop
             },
         }
@@ -0,0 +1,156 @@
+use gtk::prelude::*;
+
+const SECTION: &str = "perspectives";
+
+pub struct Perspective {
+    pub name: String,
+    pub query: String,
+    pub search: relm4::Controller<crate::search::Model>,
+}
+
+impl Perspective {
+    pub fn page(name: &str) -> String {
+        format!("perspective:{name}")
+    }
+
+    pub fn all() -> Vec<(String, String)> {
+        crate::config::Config::load().section(SECTION).to_vec()
+    }
+
+    pub fn save(perspectives: &[Self]) -> Result<(), String> {
+        let mut config = crate::config::Config::load();
+
+        for (name, _) in config.section(SECTION).to_vec() {
+            config.remove(SECTION, &name);
+        }
+
+        for perspective in perspectives {
+            config.set(SECTION, &perspective.name, &perspective.query);
+        }
+
+        config.save()
+    }
+
+    fn icon(query: &str) -> &'static str {
+        use crate::query::parser::Term;
+
+        let expr = crate::query::parser::parse(query).unwrap_or_default();
+
+        match expr.first() {
+            Some(Term::Project(_)) => "projects",
+            Some(Term::Context(_)) => "contexts",
+            Some(Term::Hashtag(_) | Term::Tag(..)) => "tags",
+            Some(Term::Due(_) | Term::Threshold(_)) => "agenda",
+            Some(Term::Flagged) => "flag",
+            Some(Term::Done) => "done",
+            _ => "search",
+        }
+    }
+
+    pub fn tab(&self, sender: &relm4::ComponentSender<super::Model>) -> gtk::Box {
+        let vbox = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        vbox.set_homogeneous(false);
+        vbox.set_tooltip_text(Some(&self.query));
+
+        let image = gtk::Image::from_icon_name(Self::icon(&self.query));
+        image.set_icon_size(gtk::IconSize::Large);
+        vbox.append(&image);
+
+        let label = gtk::Label::new(Some(&self.name));
+        vbox.append(&label);
+
+        let popover = self.popover(sender);
+        popover.set_parent(&vbox);
+
+        let gesture = gtk::GestureClick::new();
+        gesture.set_button(gtk::gdk::BUTTON_SECONDARY);
+        gesture.connect_pressed(gtk::glib::clone!(
+            #[strong]
+            popover,
+            move |_, _, _, _| popover.popup()
+        ));
+        vbox.add_controller(gesture);
+
+        vbox.connect_destroy(move |_| popover.unparent());
+
+        vbox
+    }
+
+    fn popover(&self, sender: &relm4::ComponentSender<super::Model>) -> gtk::Popover {
+        use gtk::glib;
+
+        let vbox = gtk::Box::new(gtk::Orientation::Vertical, 5);
+
+        let name = gtk::Entry::new();
+        name.set_text(&self.name);
+        name.set_placeholder_text(Some("Name"));
+        vbox.append(&name);
+
+        let query = gtk::Entry::new();
+        query.set_text(&self.query);
+        query.set_placeholder_text(Some("Query"));
+        vbox.append(&query);
+
+        let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 5);
+        hbox.set_homogeneous(true);
+        vbox.append(&hbox);
+
+        let popover = gtk::Popover::new();
+        popover.set_child(Some(&vbox));
+
+        let old = self.name.clone();
+
+        let delete = gtk::Button::with_label("Delete");
+        delete.add_css_class("destructive-action");
+        delete.connect_clicked(glib::clone!(
+            #[strong]
+            sender,
+            #[weak]
+            popover,
+            #[strong]
+            old,
+            move |_| {
+                popover.popdown();
+                sender.input(super::Msg::DeletePerspective(old.clone()));
+            }
+        ));
+        hbox.append(&delete);
+
+        let save = gtk::Button::with_label("Save");
+        save.add_css_class("suggested-action");
+        save.connect_clicked(glib::clone!(
+            #[strong]
+            sender,
+            #[weak]
+            popover,
+            move |_| {
+                popover.popdown();
+                sender.input(super::Msg::EditPerspective(
+                    old.clone(),
+                    name.text().to_string(),
+                    query.text().to_string(),
+                ));
+            }
+        ));
+        hbox.append(&save);
+
+        popover
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::application::perspective::*;
+
+    #[test]
+    fn icon() {
+        assert_eq!(Perspective::icon("+work @office"), "projects");
+        assert_eq!(
+            Perspective::icon("NOT @office (#urgent OR due:today)"),
+            "tags"
+        );
+        assert_eq!(Perspective::icon("is:flagged"), "flag");
+        assert_eq!(Perspective::icon("\"call mom\""), "search");
+        assert_eq!(Perspective::icon("(+work"), "search");
+    }
+}
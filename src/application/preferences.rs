@@ -1,7 +1,51 @@
-#[derive(Clone)]
+use crate::query::group::Group;
+use crate::query::sort::Sort;
+
+const SECTION: &str = "preferences";
+const GROUP_SECTION: &str = "group";
+const SORT_SECTION: &str = "sort";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Self; 3] = [Self::System, Self::Light, Self::Dark];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+}
+
+impl std::str::FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.name() == s)
+            .ok_or_else(|| format!("Unknown theme '{s}'"))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Preferences {
     pub defered: bool,
     pub done: bool,
+    pub theme: Theme,
+    pub size: Option<(i32, i32)>,
+    pub page: u32,
+    pub pane: Option<i32>,
+    pub groups: std::collections::BTreeMap<String, Group>,
+    pub sorts: std::collections::BTreeMap<String, Sort>,
 }
 
 impl Preferences {
@@ -9,6 +53,175 @@ impl Preferences {
         Self {
             defered: false,
             done: false,
+            theme: Theme::System,
+            size: None,
+            page: 0,
+            pane: None,
+            groups: std::collections::BTreeMap::new(),
+            sorts: std::collections::BTreeMap::new(),
+        }
+    }
+
+    pub fn group(&self, page: &str) -> Group {
+        self.groups.get(page).copied().unwrap_or_default()
+    }
+
+    pub fn sort(&self, page: &str) -> Sort {
+        self.sorts.get(page).copied().unwrap_or_default()
+    }
+
+    pub fn rename_page(&mut self, old: &str, new: &str) -> bool {
+        let group = self.groups.remove(old);
+        let sort = self.sorts.remove(old);
+
+        if let Some(group) = group {
+            self.groups.insert(new.to_string(), group);
+        }
+
+        if let Some(sort) = sort {
+            self.sorts.insert(new.to_string(), sort);
+        }
+
+        group.is_some() || sort.is_some()
+    }
+
+    pub fn remove_page(&mut self, page: &str) -> bool {
+        let group = self.groups.remove(page);
+        let sort = self.sorts.remove(page);
+
+        group.is_some() || sort.is_some()
+    }
+
+    pub fn load() -> Self {
+        Self::from_config(&crate::config::Config::load())
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let mut config = crate::config::Config::load();
+        self.to_config(&mut config);
+
+        config.save()
+    }
+
+    fn from_config(config: &crate::config::Config) -> Self {
+        let default = Self::new();
+
+        let width = Self::get(config, "width");
+        let height = Self::get(config, "height");
+
+        Self {
+            defered: Self::get(config, "defered").unwrap_or(default.defered),
+            done: Self::get(config, "done").unwrap_or(default.done),
+            theme: Self::get(config, "theme").unwrap_or(default.theme),
+            size: width.zip(height).or(default.size),
+            page: Self::get(config, "page").unwrap_or(default.page),
+            pane: Self::get(config, "pane").or(default.pane),
+            groups: Self::pages(config, GROUP_SECTION),
+            sorts: Self::pages(config, SORT_SECTION),
+        }
+    }
+
+    fn to_config(&self, config: &mut crate::config::Config) {
+        config.set(SECTION, "defered", &self.defered.to_string());
+        config.set(SECTION, "done", &self.done.to_string());
+        config.set(SECTION, "theme", self.theme.name());
+
+        if let Some((width, height)) = self.size {
+            config.set(SECTION, "width", &width.to_string());
+            config.set(SECTION, "height", &height.to_string());
         }
+
+        config.set(SECTION, "page", &self.page.to_string());
+
+        if let Some(pane) = self.pane {
+            config.set(SECTION, "pane", &pane.to_string());
+        }
+
+        Self::set_pages(config, GROUP_SECTION, &self.groups, Group::name);
+        Self::set_pages(config, SORT_SECTION, &self.sorts, Sort::name);
+    }
+
+    fn pages<T>(
+        config: &crate::config::Config,
+        section: &str,
+    ) -> std::collections::BTreeMap<String, T>
+    where
+        T: std::str::FromStr<Err = String>,
+    {
+        config
+            .section(section)
+            .iter()
+            .filter_map(|(page, value)| match value.parse() {
+                Ok(value) => Some((page.clone(), value)),
+                Err(err) => {
+                    log::warn!("{err}");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn set_pages<T>(
+        config: &mut crate::config::Config,
+        section: &str,
+        pages: &std::collections::BTreeMap<String, T>,
+        name: fn(&T) -> &'static str,
+    ) {
+        for (page, _) in config.section(section).to_vec() {
+            config.remove(section, &page);
+        }
+
+        for (page, value) in pages {
+            config.set(section, page, name(value));
+        }
+    }
+
+    fn get<T: std::str::FromStr>(config: &crate::config::Config, key: &str) -> Option<T> {
+        let value = config.get(SECTION, key)?;
+
+        match value.parse() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                log::warn!("Invalid preference {key} = {value}");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::application::preferences::*;
+
+    #[test]
+    fn round_trip() {
+        let preferences = Preferences {
+            defered: true,
+            theme: Theme::Dark,
+            size: Some((1024, 768)),
+            page: 3,
+            pane: Some(600),
+            groups: [("done".to_string(), Group::Created)].into(),
+            sorts: [("inbox".to_string(), Sort::Due)].into(),
+            ..Preferences::new()
+        };
+
+        let mut config = crate::config::Config::default();
+        preferences.to_config(&mut config);
+
+        assert_eq!(config.get(SECTION, "theme"), Some("dark"));
+        assert_eq!(Preferences::from_config(&config), preferences);
+        assert_eq!(preferences.sort("inbox"), Sort::Due);
+        assert_eq!(preferences.sort("done"), Sort::Priority);
+        assert_eq!(preferences.group("done"), Group::Created);
+        assert_eq!(preferences.group("inbox"), Group::None);
+    }
+
+    #[test]
+    fn invalid() {
+        let config =
+            crate::config::Config::parse("[preferences]\ndone = yes\ntheme = blue\nwidth = 800\n");
+
+        assert_eq!(Preferences::from_config(&config), Preferences::new());
     }
 }
@@ -0,0 +1,55 @@
+#[derive(Clone, Debug)]
+pub struct Workspace {
+    pub name: String,
+    pub todo_file: String,
+    pub done_file: String,
+}
+
+impl Workspace {
+    pub fn all(config: &todo_txt::Config) -> Vec<Self> {
+        let mut workspaces = vec![Self::from_config(config)];
+
+        workspaces.extend(Self::from_env());
+
+        workspaces
+    }
+
+    fn from_config(config: &todo_txt::Config) -> Self {
+        let name = std::path::Path::new(&config.todo_file)
+            .parent()
+            .and_then(|x| x.file_name())
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_else(|| "todo".to_string());
+
+        Self {
+            name,
+            todo_file: config.todo_file.clone(),
+            done_file: config.done_file.clone(),
+        }
+    }
+
+    fn from_env() -> Vec<Self> {
+        let Some(value) = std::env::var_os("EFFITASK_WORKSPACES") else {
+            return Vec::new();
+        };
+
+        std::env::split_paths(&value)
+            .filter_map(|entry| {
+                let entry = entry.to_string_lossy().to_string();
+
+                let Some((name, dir)) = entry.split_once('=') else {
+                    log::warn!("Invalid workspace '{entry}', expected name=directory");
+                    return None;
+                };
+
+                let dir = std::path::Path::new(dir);
+
+                Some(Self {
+                    name: name.to_string(),
+                    todo_file: dir.join("todo.txt").to_string_lossy().to_string(),
+                    done_file: dir.join("done.txt").to_string_lossy().to_string(),
+                })
+            })
+            .collect()
+    }
+}
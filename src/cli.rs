@@ -0,0 +1,302 @@
+use crate::application::Preferences;
+use crate::tasks::{List, Task};
+
+#[derive(Default)]
+struct Options {
+    json: bool,
+    defered: bool,
+    done: bool,
+    notes: bool,
+}
+
+impl Options {
+    fn preferences(&self) -> Preferences {
+        Preferences {
+            defered: self.defered,
+            done: self.done,
+            ..Preferences::new()
+        }
+    }
+}
+
+pub fn run(config: &todo_txt::Config, args: &[String]) -> Result<(), String> {
+    let (options, params) = parse(args);
+
+    let mut list = List::from_files(&config.todo_file, &config.done_file);
+    list.set_auto_archive(config.auto_archive);
+
+    match params.as_slice() {
+        ["list", view] => list_view(&list, &options, view),
+        ["search", query @ ..] => {
+            let query = crate::query::parser::parse(&query.join(" "))?;
+            let today = crate::date::today();
+
+            let tasks = if options.notes {
+                let mut notes = crate::tasks::Index::new();
+                notes.update(&list);
+
+                crate::query::search(&list, &query, today, Some(&notes))
+            } else {
+                crate::query::search(&list, &query, today, None)
+            };
+            let tasks = tasks.into_iter().map(|x| x.task).collect::<Vec<_>>();
+
+            print_tasks(&tasks, &options);
+            Ok(())
+        }
+        ["add", text @ ..] if !text.is_empty() => add(&mut list, &options, &text.join(" ")),
+        ["done", id] => done(&mut list, &options, id),
+        _ => Err(format!("Invalid command '{}'", params.join(" "))),
+    }
+}
+
+fn parse(args: &[String]) -> (Options, Vec<&str>) {
+    let mut options = Options::default();
+    let mut params = Vec::new();
+    let mut args = args.iter().map(String::as_str);
+
+    for arg in args.by_ref() {
+        match arg {
+            "--" => break,
+            "--json" => options.json = true,
+            "--defered" => options.defered = true,
+            "--done" => options.done = true,
+            "--notes" => options.notes = true,
+            _ => params.push(arg),
+        }
+    }
+
+    params.extend(args);
+
+    (options, params)
+}
+
+fn list_view(list: &List, options: &Options, view: &str) -> Result<(), String> {
+    let preferences = options.preferences();
+    let today = crate::date::today();
+
+    let tasks = match view {
+        "inbox" => crate::query::inbox(list, &preferences, today),
+        "flag" => crate::query::flag(list, &preferences, today),
+        "done" => crate::query::done(list),
+        "agenda" => {
+            print_agenda(list, options, today);
+            return Ok(());
+        }
+        _ => return Err(format!("Unknown view '{view}'")),
+    };
+
+    print_tasks(&tasks, options);
+
+    Ok(())
+}
+
+fn add(list: &mut List, options: &Options, text: &str) -> Result<(), String> {
+    use std::str::FromStr as _;
+
+    let line = crate::tasks::quick_add::parse(text, crate::date::today());
+    let mut task = Task::from_str(&line).map_err(|_| format!("Unable to create task: '{text}'"))?;
+    task.create_date = Some(crate::date::today());
+
+    let command = crate::tasks::history::Command::new("Task created").add(task.clone());
+    crate::tasks::History::new().execute(list, command)?;
+
+    list.reload();
+
+    if let Some(id) = list.find(&Task::from(task.to_string())) {
+        print_tasks(&[list.tasks[id].clone()], options);
+    }
+
+    Ok(())
+}
+
+fn done(list: &mut List, options: &Options, id: &str) -> Result<(), String> {
+    let task = id
+        .parse::<usize>()
+        .ok()
+        .and_then(|x| x.checked_sub(1))
+        .and_then(|x| list.tasks.get(x))
+        .cloned()
+        .ok_or_else(|| format!("Invalid task id '{id}'"))?;
+
+    if task.finished {
+        return Err(format!("Task {id} already done"));
+    }
+
+    let command = crate::tasks::history::Command::complete(&task);
+    crate::tasks::History::new().execute(list, command)?;
+
+    print_tasks(&[list.tasks[task.id].clone()], options);
+
+    Ok(())
+}
+
+fn print_agenda(list: &List, options: &Options, today: chrono::NaiveDate) {
+    let preferences = options.preferences();
+    let mut sections = Vec::new();
+
+    for period in crate::query::Period::ALL {
+        let mut tasks = crate::query::agenda(list, &preferences, period, today);
+        sort(&mut tasks);
+
+        sections.push((period.name(), tasks));
+    }
+
+    if options.json {
+        let sections = sections
+            .iter()
+            .map(|(name, tasks)| format!("{}:{}", json::string(name), json::tasks(tasks)))
+            .collect::<Vec<_>>();
+
+        println!("{{{}}}", sections.join(","));
+    } else {
+        for (name, tasks) in sections.iter().filter(|(_, tasks)| !tasks.is_empty()) {
+            println!("{name}:");
+
+            for task in tasks {
+                println!("  {}", plain(task));
+            }
+        }
+    }
+}
+
+fn print_tasks(tasks: &[Task], options: &Options) {
+    let mut tasks = tasks.to_vec();
+    sort(&mut tasks);
+
+    if options.json {
+        println!("{}", json::tasks(&tasks));
+    } else {
+        for task in &tasks {
+            println!("{}", plain(task));
+        }
+    }
+}
+
+fn sort(tasks: &mut [Task]) {
+    crate::query::sort::sort(tasks, crate::query::sort::Sort::default());
+}
+
+fn plain(task: &Task) -> String {
+    format!("{} {task}", task.id + 1)
+}
+
+fn priority(task: &Task) -> Option<char> {
+    if task.priority.is_lowest() {
+        None
+    } else {
+        Some((b'A' + u8::from(task.priority.clone())) as char)
+    }
+}
+
+mod json {
+    use crate::tasks::Task;
+
+    pub fn tasks(tasks: &[Task]) -> String {
+        let tasks = tasks.iter().map(task).collect::<Vec<_>>();
+
+        format!("[{}]", tasks.join(","))
+    }
+
+    fn task(task: &Task) -> String {
+        let fields = [
+            ("id", (task.id + 1).to_string()),
+            ("text", string(&task.to_string())),
+            ("subject", string(&task.subject)),
+            ("priority", option(super::priority(task))),
+            ("finished", task.finished.to_string()),
+            ("flagged", task.flagged.to_string()),
+            ("create_date", option(task.create_date)),
+            ("finish_date", option(task.finish_date)),
+            ("due_date", option(task.due_date)),
+            ("threshold_date", option(task.threshold_date)),
+            ("projects", strings(&task.projects)),
+            ("contexts", strings(&task.contexts)),
+            ("hashtags", strings(&task.hashtags)),
+        ];
+
+        let fields = fields
+            .iter()
+            .map(|(key, value)| format!("{}:{value}", string(key)))
+            .collect::<Vec<_>>();
+
+        format!("{{{}}}", fields.join(","))
+    }
+
+    fn option<T: ToString>(value: Option<T>) -> String {
+        match value {
+            Some(value) => string(&value.to_string()),
+            None => "null".to_string(),
+        }
+    }
+
+    fn strings(values: &[String]) -> String {
+        let values = values.iter().map(|x| string(x)).collect::<Vec<_>>();
+
+        format!("[{}]", values.join(","))
+    }
+
+    pub fn string(value: &str) -> String {
+        let mut escaped = String::from('"');
+
+        for c in value.chars() {
+            match c {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+                c => escaped.push(c),
+            }
+        }
+
+        escaped.push('"');
+
+        escaped
+    }
+
+    #[cfg(test)]
+    mod tests {
+        #[test]
+        fn string() {
+            assert_eq!(super::string("call \"mom\""), r#""call \"mom\"""#);
+            assert_eq!(super::string("a\\b\n"), r#""a\\b\n""#);
+            assert_eq!(super::string("\u{1}"), r#""\u0001""#);
+        }
+
+        #[test]
+        fn tasks() {
+            let mut task =
+                crate::tasks::Task::from("(A) Call \"mom\" +family due:2024-01-02".to_string());
+            task.id = 2;
+
+            assert_eq!(
+                super::tasks(&[task]),
+                concat!(
+                    r#"[{"id":3,"text":"(A) Call \"mom\" +family due:2024-01-02","#,
+                    r#""subject":"Call \"mom\" +family","priority":"A","finished":false,"#,
+                    r#""flagged":false,"create_date":null,"finish_date":null,"#,
+                    r#""due_date":"2024-01-02","threshold_date":null,"#,
+                    r#""projects":["family"],"contexts":[],"hashtags":[]}]"#,
+                )
+            );
+            assert_eq!(super::tasks(&[]), "[]");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::*;
+
+    #[test]
+    fn options() {
+        let args = ["search", "--notes", "milk", "--", "--done", "--json"].map(str::to_string);
+        let (options, params) = parse(&args);
+
+        assert!(options.notes);
+        assert!(!options.done && !options.json);
+        assert_eq!(params, ["search", "milk", "--done", "--json"]);
+    }
+}
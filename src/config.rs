@@ -0,0 +1,187 @@
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Section {
+    name: String,
+    entries: Vec<(String, String)>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    sections: Vec<Section>,
+}
+
+impl Config {
+    pub fn path() -> std::path::PathBuf {
+        let dir = match std::env::var_os("XDG_CONFIG_HOME").map(std::path::PathBuf::from) {
+            Some(dir) if dir.is_absolute() => dir,
+            _ => std::path::PathBuf::from(std::env::var_os("HOME").unwrap_or_default())
+                .join(".config"),
+        };
+
+        dir.join(crate::application::NAME).join("config.ini")
+    }
+
+    pub fn load() -> Self {
+        match std::fs::read_to_string(Self::path()) {
+            Ok(content) => Self::parse(&content),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path();
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|err| format!("Unable to create {}: {err}", dir.display()))?;
+        }
+
+        crate::tasks::List::atomic_write(&path.to_string_lossy(), self.to_string().as_bytes())
+            .map_err(|err| format!("Unable to write {}: {err}", path.display()))
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let mut config = Self::default();
+        let mut section = String::new();
+
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+                section = name.trim().to_string();
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                log::warn!("Invalid configuration line '{line}'");
+                continue;
+            };
+
+            config.set(&section, key.trim(), value.trim());
+        }
+
+        config
+    }
+
+    pub fn is_valid_key(key: &str) -> bool {
+        let key = key.trim();
+
+        !key.is_empty() && !key.contains('=') && !key.starts_with(['[', '#', ';'])
+    }
+
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.section(section)
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn section(&self, name: &str) -> &[(String, String)] {
+        self.sections
+            .iter()
+            .find(|x| x.name == name)
+            .map(|x| x.entries.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn set(&mut self, section: &str, key: &str, value: &str) {
+        let entries = &mut self.section_mut(section).entries;
+
+        match entries.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value.to_string(),
+            None => entries.push((key.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn remove(&mut self, section: &str, key: &str) {
+        self.section_mut(section).entries.retain(|(k, _)| k != key);
+    }
+
+    fn section_mut(&mut self, name: &str) -> &mut Section {
+        let position = match self.sections.iter().position(|x| x.name == name) {
+            Some(position) => position,
+            None => {
+                self.sections.push(Section {
+                    name: name.to_string(),
+                    entries: Vec::new(),
+                });
+
+                self.sections.len() - 1
+            }
+        };
+
+        &mut self.sections[position]
+    }
+}
+
+impl std::fmt::Display for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut first = true;
+
+        for section in &self.sections {
+            if section.entries.is_empty() {
+                continue;
+            }
+
+            if !first {
+                writeln!(f)?;
+            }
+            first = false;
+
+            if !section.name.is_empty() {
+                writeln!(f, "[{}]", section.name)?;
+            }
+
+            for (key, value) in &section.entries {
+                writeln!(f, "{key} = {value}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::*;
+
+    #[test]
+    fn parse() {
+        let config = Config::parse(
+            "# comment\nglobal = 1\n\n[perspectives]\nWork = +work AND NOT is:done\ninvalid\n",
+        );
+
+        assert_eq!(
+            config.section(""),
+            [("global".to_string(), "1".to_string())]
+        );
+        assert_eq!(
+            config.section("perspectives"),
+            [("Work".to_string(), "+work AND NOT is:done".to_string())]
+        );
+        assert!(config.section("unknown").is_empty());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut config = Config::default();
+        config.set("perspectives", "Work", "+work");
+        config.set("perspectives", "Home", "@home");
+        config.set("perspectives", "Work", "+work OR +office");
+        config.remove("perspectives", "Home");
+
+        let content = config.to_string();
+
+        assert_eq!(content, "[perspectives]\nWork = +work OR +office\n");
+        assert_eq!(Config::parse(&content), config);
+    }
+
+    #[test]
+    fn valid_key() {
+        assert!(Config::is_valid_key("Work stuff"));
+        assert!(!Config::is_valid_key(" "));
+        assert!(!Config::is_valid_key("a=b"));
+        assert!(!Config::is_valid_key("[work]"));
+    }
+}
@@ -14,7 +14,7 @@ impl Model {
         use relm4::ComponentController as _;
 
         let list = crate::application::tasks();
-        let tasks = list.tasks.iter().filter(|x| x.finished).cloned().collect();
+        let tasks = crate::query::done(&list);
 
         self.tasks
             .sender()
@@ -37,7 +37,7 @@ impl relm4::SimpleComponent for Model {
         use relm4::ComponentController as _;
 
         let tasks = crate::widgets::tasks::Model::builder()
-            .launch(())
+            .launch("done".to_string())
             .forward(sender.output_sender(), std::convert::identity);
 
         let model = Self { tasks };
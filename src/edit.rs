@@ -4,6 +4,8 @@ use relm4::ComponentController as _;
 #[derive(Debug)]
 pub enum MsgInput {
     Ok,
+    Delete,
+    Duplicate,
     Flag(bool),
     Set(Box<crate::tasks::Task>),
     UpdateDate(DateType, Option<chrono::NaiveDate>),
@@ -15,7 +17,9 @@ pub enum MsgInput {
 #[derive(Debug)]
 pub enum MsgOutput {
     Cancel,
+    Delete(Box<crate::tasks::Task>),
     Done(Box<crate::tasks::Task>),
+    Duplicate(Box<crate::tasks::Task>),
 }
 
 pub struct Model {
@@ -142,6 +146,16 @@ impl relm4::Component for Model {
         use MsgInput::*;
 
         match msg {
+            Delete => {
+                sender
+                    .output(MsgOutput::Delete(Box::new(self.task.clone())))
+                    .ok();
+            }
+            Duplicate => {
+                sender
+                    .output(MsgOutput::Duplicate(Box::new(self.task.clone())))
+                    .ok();
+            }
             Flag(flagged) => self.task.flagged = flagged,
             Ok => {
                 let start = widgets.buffer.start_iter();
@@ -263,6 +277,19 @@ impl relm4::Component for Model {
                             sender.output(MsgOutput::Cancel).ok();
                         },
                     },
+                    pack_end = &gtk::Button {
+                        add_css_class: "destructive-action",
+                        set_icon_name: "edit-delete",
+                        set_tooltip_text: Some("Delete"),
+
+                        connect_clicked => MsgInput::Delete,
+                    },
+                    pack_end = &gtk::Button {
+                        set_icon_name: "edit-copy",
+                        set_tooltip_text: Some("Duplicate"),
+
+                        connect_clicked => MsgInput::Duplicate,
+                    },
                 },
             },
         }
@@ -13,22 +13,9 @@ impl Model {
     fn update_tasks(&mut self) {
         use relm4::ComponentController as _;
 
-        let today = crate::date::today();
-
         let list = crate::application::tasks();
         let preferences = crate::application::preferences();
-        let tasks = list
-            .tasks
-            .iter()
-            .filter(|x| {
-                !x.finished
-                    && x.projects.is_empty()
-                    && (preferences.defered
-                        || x.threshold_date.is_none()
-                        || x.threshold_date.unwrap() <= today)
-            })
-            .cloned()
-            .collect();
+        let tasks = crate::query::inbox(&list, &preferences, crate::date::today());
 
         self.tasks
             .sender()
@@ -51,7 +38,7 @@ impl relm4::SimpleComponent for Model {
         use relm4::ComponentController as _;
 
         let tasks = crate::widgets::tasks::Model::builder()
-            .launch(())
+            .launch("inbox".to_string())
             .forward(sender.output_sender(), std::convert::identity);
 
         let model = Self { tasks };
@@ -40,10 +40,18 @@ pub enum Msg {
     Add(ChannelData),
     Clear,
     Read(gtk::ListBoxRow),
+    Undo(usize),
+    Undoable(String, usize),
+}
+
+#[derive(Debug)]
+pub enum MsgOutput {
+    Undo(usize),
 }
 
 pub struct Model {
     count: usize,
+    undo: Option<gtk::Button>,
 }
 
 impl Model {
@@ -67,6 +75,31 @@ impl Model {
 
         widgets.list_box.append(&label);
     }
+
+    fn add_undoable(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: relm4::ComponentSender<Self>,
+        text: &str,
+        id: usize,
+    ) {
+        let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 5);
+
+        let label = gtk::Label::new(Some(text));
+        label.add_css_class("info");
+        label.set_hexpand(true);
+        hbox.append(&label);
+
+        let button = gtk::Button::with_label("Undo");
+        button.connect_clicked(move |_| sender.input(Msg::Undo(id)));
+        hbox.append(&button);
+
+        if let Some(previous) = self.undo.replace(button) {
+            previous.set_visible(false);
+        }
+
+        widgets.list_box.append(&hbox);
+    }
 }
 
 #[relm4::component(pub)]
@@ -74,7 +107,7 @@ impl relm4::Component for Model {
     type CommandOutput = ();
     type Init = ();
     type Input = Msg;
-    type Output = ();
+    type Output = MsgOutput;
 
     fn init(
         _: Self::Init,
@@ -87,7 +120,10 @@ impl relm4::Component for Model {
         log::set_max_level(log::LevelFilter::Info);
         log::set_boxed_logger(Box::new(log)).unwrap_or_default();
 
-        let model = Self { count: 0 };
+        let model = Self {
+            count: 0,
+            undo: None,
+        };
 
         let widgets = view_output!();
 
@@ -101,7 +137,7 @@ impl relm4::Component for Model {
         &mut self,
         widgets: &mut Self::Widgets,
         msg: Self::Input,
-        _: relm4::ComponentSender<Self>,
+        sender: relm4::ComponentSender<Self>,
         _: &Self::Root,
     ) {
         match msg {
@@ -112,11 +148,23 @@ impl relm4::Component for Model {
             Msg::Clear => {
                 widgets.list_box.remove_all();
                 self.count = 0;
+                self.undo = None;
             }
             Msg::Read(row) => {
                 widgets.list_box.remove(&row);
                 self.count = self.count.saturating_sub(1);
             }
+            Msg::Undo(id) => {
+                if let Some(button) = self.undo.take() {
+                    button.set_visible(false);
+                }
+
+                sender.output(MsgOutput::Undo(id)).ok();
+            }
+            Msg::Undoable(text, id) => {
+                self.add_undoable(widgets, sender, &text, id);
+                self.count += 1;
+            }
         }
     }
 
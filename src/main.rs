@@ -2,12 +2,16 @@
 
 mod agenda;
 mod application;
+mod cli;
+mod config;
 mod date;
 mod done;
 mod edit;
 mod flag;
 mod inbox;
 mod logger;
+mod preferences;
+mod query;
 mod search;
 mod tasks;
 mod widgets;
@@ -17,10 +21,37 @@ fn main() {
 
     envir::init();
 
-    if std::env::args().nth(1).as_deref() == Some("usage") {
-        usage(&std::env::args().next().unwrap());
+    let mut args = std::env::args().collect::<Vec<_>>();
+    let program = args.remove(0);
 
-        std::process::exit(0);
+    // todo.sh runs add-ons as `action ACTION ARGS…`, except for `action usage`
+    if std::env::var_os("TODO_FULL_SH").is_some() && !args.is_empty() && args[0] != "usage" {
+        args.remove(0);
+    }
+
+    match args.first().map(String::as_str) {
+        None => (),
+        Some("usage") => {
+            usage(&program);
+
+            std::process::exit(0);
+        }
+        Some("list" | "search" | "add" | "done") => {
+            let config = todo_txt::Config::from_env();
+
+            if let Err(err) = cli::run(&config, &args) {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }
+
+            std::process::exit(0);
+        }
+        Some(command) => {
+            eprintln!("Unknown command '{command}'");
+            usage(&program);
+
+            std::process::exit(1);
+        }
     }
 
     let config = todo_txt::Config::from_env();
@@ -34,8 +65,21 @@ fn main() {
 fn usage(program: &str) {
     let path = std::path::Path::new(&program);
 
-    println!("    {}", path.file_name().unwrap().to_str().unwrap());
+    let name = path.file_name().unwrap().to_str().unwrap();
+
+    println!("    {name}");
     println!("      Launch focus graphical interface");
+    println!("    {name} list inbox|flag|agenda|done [--json] [--defered] [--done]");
+    println!("      Print tasks displayed by a view");
+    println!("    {name} search QUERY [--json] [--notes]");
+    println!("      Print tasks matching QUERY");
+    println!("    {name} add TEXT [--json]");
+    println!("      Add a task");
+    println!("    {name} done ID [--json]");
+    println!("      Mark task ID as done, ID is the number printed before the task");
+    println!("      by list or search: its position in todo.txt then done.txt,");
+    println!("      blank and comment lines excluded");
+    println!("    Options are read until --, the following arguments are taken literally");
 }
 
 fn initialize_resources() {
@@ -0,0 +1,118 @@
+use crate::application::{Preferences, Theme};
+use gtk::prelude::*;
+
+#[derive(Debug)]
+pub enum MsgInput {
+    Defered(bool),
+    Done(bool),
+    Theme(u32),
+}
+
+#[derive(Debug)]
+pub enum MsgOutput {
+    Changed(Box<Preferences>),
+}
+
+pub struct Model;
+
+impl Model {
+    fn change<F>(sender: relm4::ComponentSender<Self>, f: F)
+    where
+        F: FnOnce(&mut Preferences),
+    {
+        let mut preferences = crate::application::preferences();
+        f(&mut preferences);
+
+        sender
+            .output(MsgOutput::Changed(Box::new(preferences)))
+            .ok();
+    }
+}
+
+#[relm4::component(pub)]
+impl relm4::SimpleComponent for Model {
+    type Init = Preferences;
+    type Input = MsgInput;
+    type Output = MsgOutput;
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: relm4::ComponentSender<Self>,
+    ) -> relm4::ComponentParts<Self> {
+        let model = Self;
+
+        let themes = Theme::ALL.map(|x| x.name());
+
+        let widgets = view_output!();
+
+        relm4::ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: relm4::ComponentSender<Self>) {
+        match msg {
+            MsgInput::Defered(defered) => Self::change(sender, |x| x.defered = defered),
+            MsgInput::Done(done) => Self::change(sender, |x| x.done = done),
+            MsgInput::Theme(n) => {
+                let theme = Theme::ALL[n as usize];
+
+                Self::change(sender, |x| x.theme = theme);
+            }
+        }
+    }
+
+    view! {
+        gtk::Window {
+            set_hide_on_close: true,
+            set_modal: true,
+            set_resizable: false,
+            set_title: Some("Preferences"),
+
+            gtk::Box {
+                set_margin_bottom: 10,
+                set_margin_end: 10,
+                set_margin_start: 10,
+                set_margin_top: 10,
+                set_orientation: gtk::Orientation::Vertical,
+                set_spacing: 10,
+
+                gtk::CheckButton {
+                    set_label: Some("Display defered tasks"),
+                    set_active: init.defered,
+
+                    connect_toggled[sender] => move |this| {
+                        sender.input(MsgInput::Defered(this.is_active()));
+                    },
+                },
+                gtk::CheckButton {
+                    set_label: Some("Display done tasks"),
+                    set_active: init.done,
+
+                    connect_toggled[sender] => move |this| {
+                        sender.input(MsgInput::Done(this.is_active()));
+                    },
+                },
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 10,
+
+                    gtk::Label {
+                        set_hexpand: true,
+                        set_text: "Theme",
+                        set_xalign: 0.,
+                    },
+                    gtk::DropDown::from_strings(&themes) {
+                        set_selected: Theme::ALL
+                            .iter()
+                            .position(|x| *x == init.theme)
+                            .unwrap_or_default() as u32,
+
+                        connect_selected_notify[sender] => move |this| {
+                            sender.input(MsgInput::Theme(this.selected()));
+                        },
+                    },
+                },
+            },
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::tasks::Task;
+
+const MATCH: i64 = 16;
+const CONSECUTIVE: i64 = 8;
+const WORD_START: i64 = 12;
+const GAP: i64 = 1;
+const MISS: i64 = 20;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Match {
+    pub score: i64,
+    pub ranges: Vec<(usize, usize)>,
+}
+
+pub fn find(pattern: &str, text: &str) -> Option<Match> {
+    let pattern = pattern
+        .chars()
+        .flat_map(char::to_lowercase)
+        .collect::<Vec<_>>();
+
+    if pattern.is_empty() {
+        return Some(Match::default());
+    }
+
+    let chars = text
+        .char_indices()
+        .map(|(i, c)| (i, c.len_utf8(), c.to_lowercase().next().unwrap_or(c)))
+        .collect::<Vec<_>>();
+    let allowed = pattern.len() / 4;
+
+    (0..chars.len())
+        .filter(|&start| pattern[..=allowed].contains(&chars[start].2))
+        .filter_map(|start| walk(&pattern, &chars, start, allowed))
+        .max_by_key(|x| x.score)
+}
+
+fn walk(
+    pattern: &[char],
+    chars: &[(usize, usize, char)],
+    start: usize,
+    allowed: usize,
+) -> Option<Match> {
+    let mut result = Match::default();
+    let mut cursor = start;
+    let mut last: Option<usize> = None;
+    let mut misses = 0;
+
+    for c in pattern {
+        let Some(offset) = chars[cursor..].iter().position(|x| x.2 == *c) else {
+            misses += 1;
+            result.score -= MISS;
+
+            if misses > allowed {
+                return None;
+            }
+
+            continue;
+        };
+
+        let i = cursor + offset;
+        let (position, len, _) = chars[i];
+
+        result.score += MATCH;
+
+        match last {
+            Some(last) if last + 1 == i => {
+                result.score += CONSECUTIVE;
+
+                if let Some(range) = result.ranges.last_mut() {
+                    range.1 = position + len;
+                }
+            }
+            Some(last) => {
+                result.score -= (i - last - 1) as i64 * GAP;
+                result.ranges.push((position, position + len));
+            }
+            None => result.ranges.push((position, position + len)),
+        }
+
+        if i == 0 || !chars[i - 1].2.is_alphanumeric() {
+            result.score += WORD_START;
+        }
+
+        last = Some(i);
+        cursor = i + 1;
+    }
+
+    (!result.ranges.is_empty()).then_some(result)
+}
+
+pub fn task(pattern: &str, task: &Task) -> Option<Match> {
+    let subject = find(pattern, &task.subject);
+
+    let tags = task
+        .projects
+        .iter()
+        .chain(&task.contexts)
+        .filter_map(|x| find(pattern, x))
+        .map(|x| Match {
+            score: x.score,
+            ranges: Vec::new(),
+        });
+
+    subject.into_iter().chain(tags).max_by_key(|x| x.score)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::fuzzy::*;
+
+    #[test]
+    fn find() {
+        assert_eq!(
+            super::find("mom", "Call Mom"),
+            Some(Match {
+                score: 3 * MATCH + 2 * CONSECUTIVE + WORD_START,
+                ranges: vec![(5, 8)],
+            })
+        );
+        assert_eq!(
+            super::find("mlik", "Buy milk").map(|x| x.ranges),
+            Some(vec![(4, 5), (6, 8)])
+        );
+        assert_eq!(super::find("mom", "Fix bike"), None);
+        assert_eq!(super::find("", "Fix bike"), Some(Match::default()));
+    }
+
+    #[test]
+    fn rank() {
+        let exact = super::find("mom", "Call mom").unwrap();
+        let scattered = super::find("mom", "Make an order of milk").unwrap();
+
+        assert!(exact.score > scattered.score);
+    }
+
+    #[test]
+    fn task() {
+        let task = Task::from("Call dad @phone".to_string());
+
+        assert!(super::task("phone", &task).is_some());
+        assert!(super::task("mom", &task).is_none());
+    }
+}
@@ -0,0 +1,213 @@
+use crate::tasks::Task;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Group {
+    #[default]
+    None,
+    Project,
+    Context,
+    Priority,
+    Due,
+    Created,
+}
+
+impl Group {
+    pub const ALL: [Self; 6] = [
+        Self::None,
+        Self::Project,
+        Self::Context,
+        Self::Priority,
+        Self::Due,
+        Self::Created,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Project => "project",
+            Self::Context => "context",
+            Self::Priority => "priority",
+            Self::Due => "due",
+            Self::Created => "created",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::None => "No grouping",
+            Self::Project => "Project",
+            Self::Context => "Context",
+            Self::Priority => "Priority",
+            Self::Due => "Due date",
+            Self::Created => "Creation month",
+        }
+    }
+
+    fn key(&self, task: &Task, today: chrono::NaiveDate) -> ((i64, String), String) {
+        match self {
+            Self::None => ((0, String::new()), String::new()),
+            Self::Project => match task.projects.iter().min() {
+                Some(project) => ((0, project.clone()), format!("+{project}")),
+                None => ((1, String::new()), "No project".to_string()),
+            },
+            Self::Context => match task.contexts.iter().min() {
+                Some(context) => ((0, context.clone()), format!("@{context}")),
+                None => ((1, String::new()), "No context".to_string()),
+            },
+            Self::Priority => {
+                if task.priority.is_lowest() {
+                    ((i64::MAX, String::new()), "No priority".to_string())
+                } else {
+                    let priority = u8::from(task.priority.clone());
+
+                    (
+                        (priority.into(), String::new()),
+                        format!("Priority {}", (b'A' + priority) as char),
+                    )
+                }
+            }
+            Self::Due => {
+                let Some(due_date) = task.due_date else {
+                    return ((i64::MAX, String::new()), "No due date".to_string());
+                };
+
+                let labels = ["Past due", "Today", "Tomorrow", "This week", "This month"];
+
+                for (n, period) in super::Period::ALL.iter().enumerate() {
+                    let (start, end) = period.range(today);
+
+                    if start.map_or(true, |x| due_date >= x) && end.map_or(true, |x| due_date < x) {
+                        return ((n as i64, String::new()), labels[n].to_string());
+                    }
+                }
+
+                ((labels.len() as i64, String::new()), "Later".to_string())
+            }
+            Self::Created => {
+                use chrono::Datelike as _;
+
+                match task.create_date {
+                    Some(date) => (
+                        (
+                            -i64::from(date.year() * 12 + date.month() as i32),
+                            String::new(),
+                        ),
+                        date.format("%B %Y").to_string(),
+                    ),
+                    None => ((i64::MAX, String::new()), "No creation date".to_string()),
+                }
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for Group {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.name() == s)
+            .ok_or_else(|| format!("Unknown group '{s}'"))
+    }
+}
+
+pub fn group<T: std::borrow::Borrow<Task> + Clone>(
+    tasks: &[T],
+    group: Group,
+    today: chrono::NaiveDate,
+) -> Vec<(String, Vec<T>)> {
+    let mut groups = std::collections::BTreeMap::<_, (String, Vec<T>)>::new();
+
+    for task in tasks {
+        let (key, label) = group.key(task.borrow(), today);
+
+        groups
+            .entry(key)
+            .or_insert_with(|| (label, Vec::new()))
+            .1
+            .push(task.clone());
+    }
+
+    groups.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::group::*;
+
+    fn labels(lines: &[&str], by: Group) -> Vec<(String, usize)> {
+        let tasks = lines
+            .iter()
+            .map(|x| Task::from(x.to_string()))
+            .collect::<Vec<_>>();
+
+        group(&tasks, by, "2024-01-10".parse().unwrap())
+            .into_iter()
+            .map(|(label, tasks)| (label, tasks.len()))
+            .collect()
+    }
+
+    #[test]
+    fn project() {
+        assert_eq!(
+            labels(
+                &[
+                    "Call mom +family",
+                    "Buy milk",
+                    "Fix bike +home",
+                    "Visit +family"
+                ],
+                Group::Project
+            ),
+            [
+                ("+family".to_string(), 2),
+                ("+home".to_string(), 1),
+                ("No project".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn due() {
+        assert_eq!(
+            labels(
+                &[
+                    "Later due:2024-03-01",
+                    "None",
+                    "Tomorrow due:2024-01-11",
+                    "Late due:2024-01-01",
+                ],
+                Group::Due
+            ),
+            [
+                ("Past due".to_string(), 1),
+                ("Tomorrow".to_string(), 1),
+                ("Later".to_string(), 1),
+                ("No due date".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn priority_and_created() {
+        assert_eq!(
+            labels(&["(B) b", "a", "(A) c"], Group::Priority),
+            [
+                ("Priority A".to_string(), 1),
+                ("Priority B".to_string(), 1),
+                ("No priority".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            labels(
+                &["2024-01-02 a", "2023-12-24 b", "2024-01-05 c"],
+                Group::Created
+            ),
+            [
+                ("January 2024".to_string(), 2),
+                ("December 2023".to_string(), 1)
+            ]
+        );
+    }
+}
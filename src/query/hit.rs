@@ -0,0 +1,119 @@
+use crate::tasks::Task;
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Snippet {
+    pub line: String,
+    pub ranges: Vec<(usize, usize)>,
+}
+
+impl Snippet {
+    pub fn find(content: &str, texts: &[&str]) -> Option<Self> {
+        content.lines().map(str::trim).find_map(|line| {
+            let mut ranges = texts
+                .iter()
+                .filter(|x| !x.is_empty())
+                .flat_map(|text| Self::matches(line, text))
+                .collect::<Vec<_>>();
+
+            if ranges.is_empty() {
+                return None;
+            }
+
+            ranges.sort();
+
+            Some(Self {
+                line: line.to_string(),
+                ranges,
+            })
+        })
+    }
+
+    fn matches(line: &str, text: &str) -> Vec<(usize, usize)> {
+        let mut lower = String::new();
+        let mut offsets = Vec::new();
+
+        for (start, c) in line.char_indices() {
+            let end = start + c.len_utf8();
+
+            for x in c.to_lowercase() {
+                lower.push(x);
+                offsets.resize(offsets.len() + x.len_utf8(), (start, end));
+            }
+        }
+
+        lower
+            .match_indices(text)
+            .map(|(start, x)| (offsets[start].0, offsets[start + x.len() - 1].1))
+            .collect()
+    }
+
+    pub fn markup(&self) -> String {
+        let mut markup = String::new();
+        let mut last = 0;
+
+        for &(start, end) in &self.ranges {
+            if start < last {
+                continue;
+            }
+
+            markup.push_str(&Self::escape(&self.line[last..start]));
+            markup.push_str(&format!("<b>{}</b>", Self::escape(&self.line[start..end])));
+            last = end;
+        }
+
+        markup.push_str(&Self::escape(&self.line[last..]));
+
+        markup
+    }
+
+    fn escape(text: &str) -> String {
+        gtk::glib::markup_escape_text(text).as_str().to_string()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Hit {
+    pub task: Task,
+    pub highlights: Vec<(usize, usize)>,
+    pub snippet: Option<Snippet>,
+}
+
+impl From<Task> for Hit {
+    fn from(task: Task) -> Self {
+        Self {
+            task,
+            highlights: Vec::new(),
+            snippet: None,
+        }
+    }
+}
+
+impl std::borrow::Borrow<Task> for Hit {
+    fn borrow(&self) -> &Task {
+        &self.task
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::hit::*;
+
+    #[test]
+    fn markup() {
+        let snippet = Snippet::find("# Title\n\n  Buy milk & Milk powder\n", &["milk"]).unwrap();
+
+        assert_eq!(snippet.markup(), "Buy <b>milk</b> &amp; <b>Milk</b> powder");
+        assert_eq!(Snippet::find("Buy eggs", &["milk"]), None);
+    }
+
+    #[test]
+    fn unicode() {
+        let snippet = Snippet::find("İstanbul trip: buy MILK", &["milk"]).unwrap();
+
+        assert_eq!(snippet.markup(), "İstanbul trip: buy <b>MILK</b>");
+
+        let snippet = Snippet::find("Straße STRAßE", &["straße"]).unwrap();
+
+        assert_eq!(snippet.markup(), "<b>Straße</b> <b>STRAßE</b>");
+    }
+}
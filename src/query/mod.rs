@@ -0,0 +1,521 @@
+pub mod fuzzy;
+pub mod group;
+pub mod hit;
+pub mod parser;
+pub mod sort;
+
+pub use hit::Hit;
+
+use crate::application::Preferences;
+use crate::tasks::{Index, List, Task};
+
+#[derive(Clone, Copy, Debug)]
+pub enum Period {
+    Past,
+    Today,
+    Tomorrow,
+    Week,
+    Month,
+}
+
+impl Period {
+    pub const ALL: [Self; 5] = [
+        Self::Past,
+        Self::Today,
+        Self::Tomorrow,
+        Self::Week,
+        Self::Month,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Past => "past",
+            Self::Today => "today",
+            Self::Tomorrow => "tomorrow",
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+
+    fn range(
+        &self,
+        date: chrono::NaiveDate,
+    ) -> (Option<chrono::NaiveDate>, Option<chrono::NaiveDate>) {
+        match self {
+            Self::Past => (None, Some(date)),
+            Self::Today => (Some(date), Some(date + chrono::Duration::days(1))),
+            Self::Tomorrow => (
+                Some(date + chrono::Duration::days(1)),
+                Some(date + chrono::Duration::days(2)),
+            ),
+            Self::Week => (
+                Some(date + chrono::Duration::days(2)),
+                Some(date + chrono::Duration::weeks(1)),
+            ),
+            Self::Month => (
+                Some(date + chrono::Duration::weeks(1)),
+                Some(date + chrono::Duration::weeks(4)),
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Tag {
+    Projects,
+    Contexts,
+    Hashtags,
+}
+
+impl Tag {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Projects => "projects",
+            Self::Contexts => "contexts",
+            Self::Hashtags => "tags",
+        }
+    }
+
+    pub fn of<'a>(&self, task: &'a Task) -> &'a [String] {
+        match self {
+            Self::Projects => &task.projects,
+            Self::Contexts => &task.contexts,
+            Self::Hashtags => &task.hashtags,
+        }
+    }
+
+    pub fn set(&self, task: &mut Task, from: &[String], name: &str) {
+        let prefix = match self {
+            Self::Projects => '+',
+            Self::Contexts => '@',
+            Self::Hashtags => '#',
+        };
+
+        let tags = match self {
+            Self::Projects => &mut task.projects,
+            Self::Contexts => &mut task.contexts,
+            Self::Hashtags => &mut task.hashtags,
+        };
+
+        let removed = from
+            .iter()
+            .filter(|x| *x != name && tags.contains(x))
+            .map(|x| format!("{prefix}{x}"))
+            .collect::<Vec<_>>();
+        tags.retain(|x| x == name || !from.contains(x));
+
+        let added = !tags.iter().any(|x| x == name);
+
+        if added {
+            tags.push(name.to_string());
+        }
+
+        let mut subject = task
+            .subject
+            .split(' ')
+            .filter(|x| !removed.iter().any(|tag| tag == x))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if added {
+            if !subject.is_empty() {
+                subject.push(' ');
+            }
+            subject.push(prefix);
+            subject.push_str(name);
+        }
+
+        task.subject = subject;
+    }
+}
+
+pub fn is_deferred(task: &Task, date: chrono::NaiveDate) -> bool {
+    task.threshold_date.is_some_and(|x| x > date)
+}
+
+pub fn is_visible(task: &Task, preferences: &Preferences, date: chrono::NaiveDate) -> bool {
+    (preferences.done || !task.finished) && (preferences.defered || !is_deferred(task, date))
+}
+
+pub fn inbox(list: &List, preferences: &Preferences, today: chrono::NaiveDate) -> Vec<Task> {
+    list.tasks
+        .iter()
+        .filter(|x| x.projects.is_empty() && is_visible(x, preferences, today))
+        .cloned()
+        .collect()
+}
+
+pub fn flag(list: &List, preferences: &Preferences, today: chrono::NaiveDate) -> Vec<Task> {
+    list.tasks
+        .iter()
+        .filter(|x| x.flagged && is_visible(x, preferences, today))
+        .cloned()
+        .collect()
+}
+
+pub fn agenda(
+    list: &List,
+    preferences: &Preferences,
+    period: Period,
+    date: chrono::NaiveDate,
+) -> Vec<Task> {
+    let (start, end) = period.range(date);
+
+    list.tasks
+        .iter()
+        .filter(|x| {
+            let Some(due_date) = x.due_date else {
+                return false;
+            };
+
+            (preferences.done || !x.finished)
+                && (preferences.defered || start.map_or(true, |start| !is_deferred(x, start)))
+                && start.map_or(true, |start| due_date >= start)
+                && end.map_or(true, |end| due_date < end)
+        })
+        .cloned()
+        .collect()
+}
+
+pub fn done(list: &List) -> Vec<Task> {
+    list.tasks.iter().filter(|x| x.finished).cloned().collect()
+}
+
+pub fn search(
+    list: &List,
+    query: &parser::Expr,
+    today: chrono::NaiveDate,
+    notes: Option<&Index>,
+) -> Vec<Hit> {
+    let texts = query.texts();
+    let found = notes.map(|notes| {
+        texts
+            .iter()
+            .map(|x| (*x, notes.find(x)))
+            .collect::<std::collections::HashMap<_, _>>()
+    });
+
+    let in_note = |task: &Task, text: &str| {
+        let Some(found) = &found else {
+            return false;
+        };
+
+        match task.note {
+            todo_txt::task::Note::Long { ref filename, .. } => {
+                found.get(text).is_some_and(|x| x.contains(filename))
+            }
+            _ => task
+                .note
+                .content()
+                .is_some_and(|x| x.to_lowercase().contains(text)),
+        }
+    };
+
+    let mut results = list
+        .tasks
+        .iter()
+        .filter(|task| {
+            query.test(&|term| match term {
+                parser::Term::Text(text) => {
+                    fuzzy::task(text, task).is_some() || in_note(task, text)
+                }
+                _ => term.matches(task, today),
+            })
+        })
+        .map(|task| {
+            let mut hit = Hit::from(task.clone());
+            let mut score = 0;
+
+            for text in &texts {
+                if let Some(x) = fuzzy::task(text, task) {
+                    score += x.score;
+                    hit.highlights.extend(x.ranges);
+                }
+            }
+
+            hit.highlights.sort_unstable();
+
+            if found.is_some() {
+                hit.snippet = task
+                    .note
+                    .content()
+                    .and_then(|content| hit::Snippet::find(&content, &texts));
+            }
+
+            (score, hit)
+        })
+        .collect::<Vec<_>>();
+
+    results.sort_by(|(_, a), (_, b)| b.task.cmp(&a.task));
+    results.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+
+    results.into_iter().map(|(_, hit)| hit).collect()
+}
+
+pub fn tags(list: &List, tag: Tag) -> Vec<(String, (u32, u32))> {
+    let tags = match tag {
+        Tag::Projects => list.projects(),
+        Tag::Contexts => list.contexts(),
+        Tag::Hashtags => list.hashtags(),
+    };
+
+    tags.into_iter()
+        .map(|x| {
+            let progress = progress(list, tag, &x);
+
+            (x, progress)
+        })
+        .filter(|&(_, (done, total))| done != total)
+        .collect()
+}
+
+pub fn progress(list: &List, tag: Tag, current: &str) -> (u32, u32) {
+    let prefix = format!("{current}-");
+
+    list.tasks
+        .iter()
+        .filter(|x| {
+            tag.of(x)
+                .iter()
+                .any(|x| x == current || x.starts_with(&prefix))
+        })
+        .fold((0, 0), |(done, total), x| {
+            (done + u32::from(x.finished), total + 1)
+        })
+}
+
+pub fn tagged(
+    list: &List,
+    preferences: &Preferences,
+    tag: Tag,
+    filters: &[String],
+    today: chrono::NaiveDate,
+) -> Vec<Task> {
+    list.tasks
+        .iter()
+        .filter(|x| {
+            let tags = tag.of(x);
+
+            !tags.is_empty()
+                && (filters.is_empty() || filters.iter().any(|x| tags.contains(x)))
+                && is_visible(x, preferences, today)
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::*;
+
+    fn date(s: &str) -> chrono::NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn list(lines: &[&str]) -> List {
+        let mut list = List::new();
+
+        for line in lines {
+            list.append(Task::from(line.to_string()));
+        }
+
+        list
+    }
+
+    fn subjects<T: std::borrow::Borrow<Task>>(tasks: &[T]) -> Vec<&str> {
+        tasks.iter().map(|x| x.borrow().subject.as_str()).collect()
+    }
+
+    fn preferences(defered: bool, done: bool) -> Preferences {
+        Preferences {
+            defered,
+            done,
+            ..Preferences::new()
+        }
+    }
+
+    #[test]
+    fn visible() {
+        let today = date("2024-01-10");
+        let task = Task::from("Call mom t:2024-01-11".to_string());
+
+        assert!(is_deferred(&task, today));
+        assert!(!is_deferred(&task, date("2024-01-11")));
+        assert!(!is_visible(&task, &preferences(false, false), today));
+        assert!(is_visible(&task, &preferences(true, false), today));
+
+        let task = Task::from("x Pay bills".to_string());
+
+        assert!(!is_visible(&task, &preferences(false, false), today));
+        assert!(is_visible(&task, &preferences(false, true), today));
+    }
+
+    #[test]
+    fn inbox() {
+        let today = date("2024-01-10");
+        let list = list(&[
+            "Call mom",
+            "Fix bike +home",
+            "x Pay bills",
+            "Buy milk t:2024-02-01",
+        ]);
+
+        assert_eq!(
+            subjects(&super::inbox(&list, &preferences(false, false), today)),
+            ["Call mom"]
+        );
+        assert_eq!(
+            subjects(&super::inbox(&list, &preferences(false, true), today)),
+            ["Call mom", "Pay bills"]
+        );
+        assert_eq!(
+            subjects(&super::inbox(&list, &preferences(true, false), today)),
+            ["Call mom", "Buy milk"]
+        );
+    }
+
+    #[test]
+    fn flag() {
+        let today = date("2024-01-10");
+        let mut list = list(&["Call mom", "Fix bike", "x Pay bills"]);
+        list.tasks[1].flagged = true;
+        list.tasks[2].flagged = true;
+
+        assert_eq!(
+            subjects(&super::flag(&list, &preferences(false, false), today)),
+            ["Fix bike"]
+        );
+        assert_eq!(
+            subjects(&super::flag(&list, &preferences(false, true), today)),
+            ["Fix bike", "Pay bills"]
+        );
+    }
+
+    #[test]
+    fn agenda() {
+        let today = date("2024-01-10");
+        let list = list(&[
+            "Late due:2024-01-01",
+            "Now due:2024-01-10",
+            "Soon due:2024-01-11",
+            "Later due:2024-01-15",
+            "Much later due:2024-02-01",
+            "Waiting due:2024-01-11 t:2024-01-12",
+            "Whenever",
+        ]);
+        let preferences = preferences(false, false);
+        let agenda =
+            |period| subjects(&super::agenda(&list, &preferences, period, today)).join(",");
+
+        assert_eq!(agenda(Period::Past), "Late");
+        assert_eq!(agenda(Period::Today), "Now");
+        assert_eq!(agenda(Period::Tomorrow), "Soon");
+        assert_eq!(agenda(Period::Week), "Later");
+        assert_eq!(agenda(Period::Month), "Much later");
+    }
+
+    #[test]
+    fn search() {
+        let today = date("2024-01-10");
+        let list = list(&["Call Mom", "Fix bike"]);
+        let query = |x| parser::parse(x).unwrap();
+
+        assert_eq!(
+            subjects(&super::search(&list, &query("mom"), today, None)),
+            ["Call Mom"]
+        );
+        assert_eq!(super::search(&list, &query(""), today, None).len(), 2);
+    }
+
+    #[test]
+    fn search_ranked() {
+        let today = date("2024-01-10");
+        let list = list(&["Make an order of milk", "Call mom", "Fix bike", "Buy mlik"]);
+        let query = |x| parser::parse(x).unwrap();
+
+        let tasks = super::search(&list, &query("mom"), today, None);
+
+        assert_eq!(subjects(&tasks), ["Call mom", "Make an order of milk"]);
+        assert_eq!(tasks[0].highlights, [(5, 8)]);
+        assert_eq!(
+            subjects(&super::search(&list, &query("milk"), today, None)),
+            ["Make an order of milk", "Buy mlik"]
+        );
+    }
+
+    #[test]
+    fn search_notes() {
+        let today = date("2024-01-10");
+        let mut list = list(&["Shopping", "Fix sink"]);
+        list.tasks[0].note = todo_txt::task::Note::Long {
+            filename: "shopping.md".to_string(),
+            content: "# Groceries\n\nBuy milk".to_string(),
+        };
+
+        let mut notes = Index::new();
+        notes.update(&list);
+
+        let query = parser::parse("milk").unwrap();
+
+        assert!(super::search(&list, &query, today, None).is_empty());
+
+        let tasks = super::search(&list, &query, today, Some(&notes));
+
+        assert_eq!(subjects(&tasks), ["Shopping"]);
+        assert_eq!(
+            tasks[0].snippet.as_ref().map(|x| x.line.as_str()),
+            Some("Buy milk")
+        );
+    }
+
+    #[test]
+    fn set() {
+        let mut task = Task::from("Call  mom +family +phone  @phone".to_string());
+
+        Tag::Projects.set(&mut task, &["family".to_string()], "home");
+        assert_eq!(task.subject, "Call  mom +phone  @phone +home");
+        assert_eq!(task.projects, ["phone", "home"]);
+
+        Tag::Contexts.set(&mut task, &[], "office");
+        assert_eq!(task.to_string(), "Call  mom +phone  @phone +home @office");
+        assert_eq!(task.contexts, ["phone", "office"]);
+
+        Tag::Contexts.set(&mut task, &["office".to_string()], "phone");
+        assert_eq!(task.to_string(), "Call  mom +phone  @phone +home");
+        assert_eq!(task.contexts, ["phone"]);
+    }
+
+    #[test]
+    fn tagged() {
+        let today = date("2024-01-10");
+        let list = list(&[
+            "Call mom +family",
+            "Fix bike +home",
+            "x Paint +home-garage",
+            "Buy milk",
+        ]);
+        let preferences = preferences(false, false);
+
+        assert_eq!(
+            subjects(&super::tagged(
+                &list,
+                &preferences,
+                Tag::Projects,
+                &[],
+                today
+            )),
+            ["Call mom +family", "Fix bike +home"]
+        );
+        assert_eq!(
+            subjects(&super::tagged(
+                &list,
+                &preferences,
+                Tag::Projects,
+                &["home".to_string()],
+                today
+            )),
+            ["Fix bike +home"]
+        );
+        assert_eq!(progress(&list, Tag::Projects, "home"), (1, 2));
+    }
+}
@@ -0,0 +1,499 @@
+use crate::tasks::Task;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Term(Term),
+}
+
+impl Default for Expr {
+    fn default() -> Self {
+        Self::And(Vec::new())
+    }
+}
+
+impl Expr {
+    pub fn test(&self, f: &dyn Fn(&Term) -> bool) -> bool {
+        match self {
+            Self::And(exprs) => exprs.iter().all(|x| x.test(f)),
+            Self::Or(exprs) => exprs.iter().any(|x| x.test(f)),
+            Self::Not(expr) => !expr.test(f),
+            Self::Term(term) => f(term),
+        }
+    }
+
+    pub fn texts(&self) -> Vec<&str> {
+        match self {
+            Self::And(exprs) | Self::Or(exprs) => exprs.iter().flat_map(Self::texts).collect(),
+            Self::Not(_) => Vec::new(),
+            Self::Term(Term::Text(text)) => vec![text.as_str()],
+            Self::Term(_) => Vec::new(),
+        }
+    }
+
+    pub fn first(&self) -> Option<&Term> {
+        match self {
+            Self::And(exprs) | Self::Or(exprs) => exprs.iter().find_map(Self::first),
+            Self::Not(_) => None,
+            Self::Term(term) => Some(term),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Term {
+    Text(String),
+    Project(String),
+    Context(String),
+    Hashtag(String),
+    Priority(u8),
+    Due(DateFilter),
+    Threshold(DateFilter),
+    Done,
+    Flagged,
+    Note,
+    Tag(String, String),
+}
+
+impl Term {
+    fn parse(word: &str) -> Result<Self, String> {
+        for (prefix, term) in [
+            ('+', Self::Project as fn(String) -> Self),
+            ('@', Self::Context),
+            ('#', Self::Hashtag),
+        ] {
+            if let Some(tag) = word.strip_prefix(prefix).filter(|x| !x.is_empty()) {
+                return Ok(term(tag.to_string()));
+            }
+        }
+
+        let Some((key, value)) = word.split_once(':').filter(|(key, _)| !key.is_empty()) else {
+            return Ok(Self::Text(word.to_lowercase()));
+        };
+
+        if value.is_empty() {
+            return Err(format!("Missing value after '{key}:'"));
+        }
+
+        let term = match (key, value) {
+            ("pri", _) => Self::Priority(Self::priority(value)?),
+            ("due", _) => Self::Due(DateFilter::parse(value)?),
+            ("t", _) => Self::Threshold(DateFilter::parse(value)?),
+            ("is", "done") => Self::Done,
+            ("is", "flagged") => Self::Flagged,
+            ("has", "note") => Self::Note,
+            ("is" | "has", _) => return Err(format!("Unknown filter '{word}'")),
+            _ => Self::Tag(key.to_string(), value.to_string()),
+        };
+
+        Ok(term)
+    }
+
+    fn priority(value: &str) -> Result<u8, String> {
+        match value.as_bytes() {
+            [c] if c.is_ascii_alphabetic() => Ok(c.to_ascii_uppercase() - b'A'),
+            _ => Err(format!("Invalid priority '{value}'")),
+        }
+    }
+
+    pub fn matches(&self, task: &Task, today: chrono::NaiveDate) -> bool {
+        match self {
+            Self::Text(text) => task.subject.to_lowercase().contains(text.as_str()),
+            Self::Project(project) => Self::has_tag(&task.projects, project),
+            Self::Context(context) => Self::has_tag(&task.contexts, context),
+            Self::Hashtag(hashtag) => Self::has_tag(&task.hashtags, hashtag),
+            Self::Priority(priority) => task.priority == *priority,
+            Self::Due(filter) => task.due_date.is_some_and(|x| filter.matches(x, today)),
+            Self::Threshold(filter) => task
+                .threshold_date
+                .is_some_and(|x| filter.matches(x, today)),
+            Self::Done => task.finished,
+            Self::Flagged => task.flagged,
+            Self::Note => task.note != todo_txt::task::Note::None,
+            Self::Tag(key, value) => task.tags.get(key) == Some(value),
+        }
+    }
+
+    fn has_tag(tags: &[String], tag: &str) -> bool {
+        let prefix = format!("{tag}-");
+
+        tags.iter().any(|x| x == tag || x.starts_with(&prefix))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Op {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl Op {
+    fn test(self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering::*;
+
+        match self {
+            Self::Lt => ordering == Less,
+            Self::Le => ordering != Greater,
+            Self::Eq => ordering == Equal,
+            Self::Ge => ordering != Less,
+            Self::Gt => ordering == Greater,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Date {
+    Relative(i64),
+    Absolute(chrono::NaiveDate),
+}
+
+impl Date {
+    fn parse(value: &str) -> Result<Self, String> {
+        let date = match value {
+            "yesterday" => Self::Relative(-1),
+            "today" => Self::Relative(0),
+            "tomorrow" => Self::Relative(1),
+            _ => Self::Absolute(
+                value
+                    .parse()
+                    .map_err(|_| format!("Invalid date '{value}'"))?,
+            ),
+        };
+
+        Ok(date)
+    }
+
+    fn resolve(&self, today: chrono::NaiveDate) -> chrono::NaiveDate {
+        match self {
+            Self::Relative(days) => today + chrono::Duration::days(*days),
+            Self::Absolute(date) => *date,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DateFilter {
+    Compare(Op, Date),
+    ThisWeek,
+    NextWeek,
+    ThisMonth,
+}
+
+impl DateFilter {
+    fn parse(value: &str) -> Result<Self, String> {
+        let filter = match value {
+            "thisweek" => Self::ThisWeek,
+            "nextweek" => Self::NextWeek,
+            "thismonth" => Self::ThisMonth,
+            _ => {
+                let (op, value) = [
+                    ("<=", Op::Le),
+                    (">=", Op::Ge),
+                    ("<", Op::Lt),
+                    (">", Op::Gt),
+                    ("=", Op::Eq),
+                ]
+                .into_iter()
+                .find_map(|(prefix, op)| value.strip_prefix(prefix).map(|x| (op, x)))
+                .unwrap_or((Op::Eq, value));
+
+                Self::Compare(op, Date::parse(value)?)
+            }
+        };
+
+        Ok(filter)
+    }
+
+    fn matches(&self, date: chrono::NaiveDate, today: chrono::NaiveDate) -> bool {
+        use chrono::Datelike as _;
+
+        let (start, end) = match self {
+            Self::Compare(op, value) => return op.test(date.cmp(&value.resolve(today))),
+            Self::ThisWeek | Self::NextWeek => {
+                let mut start =
+                    today - chrono::Duration::days(today.weekday().num_days_from_monday().into());
+
+                if matches!(self, Self::NextWeek) {
+                    start += chrono::Duration::weeks(1);
+                }
+
+                (start, start + chrono::Duration::weeks(1))
+            }
+            Self::ThisMonth => {
+                let start = today.with_day(1).unwrap();
+
+                (start, start + chrono::Months::new(1))
+            }
+        };
+
+        date >= start && date < end
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            _ => {
+                let quoted = c == '"';
+                let mut word = String::new();
+
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+
+                    chars.next();
+
+                    if c != '"' {
+                        word.push(c);
+                        continue;
+                    }
+
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some(c) => word.push(c),
+                            None => return Err("Missing closing quote".to_string()),
+                        }
+                    }
+                }
+
+                let token = match word.as_str() {
+                    _ if quoted => Token::Text(word),
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Word(word),
+                };
+
+                tokens.push(token);
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+
+        token
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut exprs = vec![self.and()?];
+
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            exprs.push(self.and()?);
+        }
+
+        Ok(Self::group(exprs, Expr::Or))
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut exprs = vec![self.not()?];
+
+        loop {
+            match self.peek() {
+                Some(Token::Or | Token::Close) | None => break,
+                Some(Token::And) => {
+                    self.position += 1;
+                    exprs.push(self.not()?);
+                }
+                Some(_) => exprs.push(self.not()?),
+            }
+        }
+
+        Ok(Self::group(exprs, Expr::And))
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.position += 1;
+
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Open) => {
+                let expr = self.or()?;
+
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err("Missing closing parenthesis".to_string()),
+                }
+            }
+            Some(Token::Word(word)) => Term::parse(&word).map(Expr::Term),
+            Some(Token::Text(text)) => Ok(Expr::Term(Term::Text(text.to_lowercase()))),
+            Some(Token::And) => Err("Unexpected AND".to_string()),
+            Some(Token::Or) => Err("Unexpected OR".to_string()),
+            Some(Token::Not) => Err("Unexpected NOT".to_string()),
+            Some(Token::Close) => Err("Unexpected closing parenthesis".to_string()),
+            None => Err("Unexpected end of query".to_string()),
+        }
+    }
+
+    fn group(mut exprs: Vec<Expr>, f: fn(Vec<Expr>) -> Expr) -> Expr {
+        if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            f(exprs)
+        }
+    }
+}
+
+pub fn parse(query: &str) -> Result<Expr, String> {
+    let tokens = tokenize(query)?;
+
+    if tokens.is_empty() {
+        return Ok(Expr::default());
+    }
+
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+    };
+    let expr = parser.or()?;
+
+    if parser.peek().is_some() {
+        return Err("Unexpected closing parenthesis".to_string());
+    }
+
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::parser::*;
+
+    fn date(s: &str) -> chrono::NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn matches(query: &str, task: &str) -> bool {
+        let task = Task::from(task.to_string());
+
+        parse(query)
+            .unwrap()
+            .test(&|term| term.matches(&task, date("2024-01-10")))
+    }
+
+    #[test]
+    fn parse_terms() {
+        assert_eq!(parse(""), Ok(Expr::And(Vec::new())));
+        assert_eq!(
+            parse("+work @home #urgent pri:a"),
+            Ok(Expr::And(vec![
+                Expr::Term(Term::Project("work".to_string())),
+                Expr::Term(Term::Context("home".to_string())),
+                Expr::Term(Term::Hashtag("urgent".to_string())),
+                Expr::Term(Term::Priority(0)),
+            ]))
+        );
+        assert_eq!(
+            parse("due:<today"),
+            Ok(Expr::Term(Term::Due(DateFilter::Compare(
+                Op::Lt,
+                Date::Relative(0)
+            ))))
+        );
+        assert_eq!(
+            parse("\"call mom\" OR NOT key:value"),
+            Ok(Expr::Or(vec![
+                Expr::Term(Term::Text("call mom".to_string())),
+                Expr::Not(Box::new(Expr::Term(Term::Tag(
+                    "key".to_string(),
+                    "value".to_string()
+                )))),
+            ]))
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse("(call").is_err());
+        assert!(parse("call)").is_err());
+        assert!(parse("\"call").is_err());
+        assert!(parse("pri:AB").is_err());
+        assert!(parse("due:someday").is_err());
+        assert!(parse("is:blue").is_err());
+        assert!(parse("due:").is_err());
+        assert!(parse("call OR").is_err());
+    }
+
+    #[test]
+    fn evaluate() {
+        assert!(matches("mom", "Call Mom +family"));
+        assert!(matches("+family", "Call mom +family-parents"));
+        assert!(!matches("+fam", "Call mom +family"));
+        assert!(matches("pri:A", "(A) Call mom"));
+        assert!(matches("due:<today", "Call mom due:2024-01-09"));
+        assert!(!matches("due:<today", "Call mom due:2024-01-10"));
+        assert!(matches("due:thisweek", "Call mom due:2024-01-14"));
+        assert!(!matches("due:thisweek", "Call mom due:2024-01-15"));
+        assert!(matches("due:nextweek", "Call mom due:2024-01-15"));
+        assert!(matches("due:thismonth", "Call mom due:2024-01-31"));
+        assert!(matches("t:>2024-01-01", "Call mom t:2024-01-02"));
+        assert!(matches("is:done", "x Call mom"));
+        assert!(matches("NOT is:done", "Call mom"));
+        assert!(matches("key:value", "Call mom key:value"));
+        assert!(matches("(bills OR mom) AND @phone", "Call mom @phone"));
+        assert!(!matches("(bills OR mom) AND @phone", "Call mom @home"));
+    }
+
+    #[test]
+    fn texts() {
+        assert_eq!(
+            parse("call (mom OR dad) NOT bills +family")
+                .unwrap()
+                .texts(),
+            ["call", "mom", "dad"]
+        );
+    }
+}
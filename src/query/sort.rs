@@ -0,0 +1,278 @@
+use crate::tasks::Task;
+use std::cmp::Ordering;
+
+const ORDER: &str = "order";
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Sort {
+    #[default]
+    Priority,
+    Due,
+    Threshold,
+    Created,
+    Project,
+    Alphabetical,
+    Manual,
+}
+
+impl Sort {
+    pub const ALL: [Self; 7] = [
+        Self::Priority,
+        Self::Due,
+        Self::Threshold,
+        Self::Created,
+        Self::Project,
+        Self::Alphabetical,
+        Self::Manual,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Priority => "priority",
+            Self::Due => "due",
+            Self::Threshold => "threshold",
+            Self::Created => "created",
+            Self::Project => "project",
+            Self::Alphabetical => "alphabetical",
+            Self::Manual => "manual",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Priority => "Priority",
+            Self::Due => "Due date",
+            Self::Threshold => "Threshold date",
+            Self::Created => "Creation date",
+            Self::Project => "Project",
+            Self::Alphabetical => "Alphabetical",
+            Self::Manual => "Manual",
+        }
+    }
+
+    fn compare(&self, a: &Task, b: &Task) -> Ordering {
+        match self {
+            Self::Priority => u8::from(a.priority.clone()).cmp(&u8::from(b.priority.clone())),
+            Self::Due => option(a.due_date, b.due_date),
+            Self::Threshold => option(a.threshold_date, b.threshold_date),
+            Self::Created => option(
+                a.create_date.map(std::cmp::Reverse),
+                b.create_date.map(std::cmp::Reverse),
+            ),
+            Self::Project => option(a.projects.iter().min(), b.projects.iter().min()),
+            Self::Alphabetical => a.subject.to_lowercase().cmp(&b.subject.to_lowercase()),
+            Self::Manual => option(order(a), order(b)).then(a.id.cmp(&b.id)),
+        }
+    }
+}
+
+impl std::str::FromStr for Sort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.name() == s)
+            .ok_or_else(|| format!("Unknown sort '{s}'"))
+    }
+}
+
+fn option<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn order(task: &Task) -> Option<i64> {
+    task.tags.get(ORDER).and_then(|x| x.parse().ok())
+}
+
+pub fn reorder(tasks: &[Task], from: usize, to: usize) -> Vec<(Task, Task)> {
+    let mut tasks = tasks.to_vec();
+
+    if from >= tasks.len() {
+        return Vec::new();
+    }
+
+    let task = tasks.remove(from);
+    let to = to.min(tasks.len());
+    tasks.insert(to, task);
+
+    let end = tasks
+        .iter()
+        .rposition(|x| order(x).is_some())
+        .map_or(to, |x| x.max(to));
+    let mut last = None;
+    let mut changes = Vec::new();
+
+    for (n, before) in tasks.iter().enumerate().take(end + 1) {
+        let current = order(before);
+        let next = tasks.get(n + 1).and_then(order);
+        let fits = current.is_some_and(|x| {
+            last.map_or(true, |last| x > last) && (n != to || next.map_or(true, |next| x < next))
+        });
+
+        let new = match current {
+            Some(x) if fits => x,
+            _ if n != to => last.map_or(1, |last| last + 1),
+            _ => match (last, next) {
+                (Some(last), Some(next)) if next - last >= 2 => last + (next - last) / 2,
+                (None, Some(next)) => next - 1,
+                (Some(last), _) => last + 1,
+                (None, None) => 1,
+            },
+        };
+
+        last = Some(new);
+
+        if current != Some(new) {
+            let mut after = before.clone();
+            after.tags.insert(ORDER.to_string(), new.to_string());
+
+            changes.push((before.clone(), after));
+        }
+    }
+
+    changes
+}
+
+pub fn sort<T: std::borrow::Borrow<Task>>(tasks: &mut [T], sort: Sort) {
+    tasks.sort_by(|a, b| {
+        let (a, b) = (a.borrow(), b.borrow());
+
+        [
+            sort,
+            Sort::Priority,
+            Sort::Due,
+            Sort::Alphabetical,
+            Sort::Manual,
+        ]
+        .iter()
+        .map(|x| x.compare(a, b))
+        .find(|x| x.is_ne())
+        .unwrap_or(Ordering::Equal)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::sort::*;
+
+    fn subjects(lines: &[&str], by: Sort) -> Vec<String> {
+        let mut tasks = lines
+            .iter()
+            .enumerate()
+            .map(|(id, line)| {
+                let mut task = Task::from(line.to_string());
+                task.id = id;
+                task
+            })
+            .collect::<Vec<_>>();
+
+        sort(&mut tasks, by);
+
+        tasks.into_iter().map(|x| x.subject.clone()).collect()
+    }
+
+    #[test]
+    fn priority() {
+        assert_eq!(
+            subjects(
+                &[
+                    "Walk dog",
+                    "(B) Pay bills due:2024-01-02",
+                    "(A) Call mom",
+                    "(B) Fix bike due:2024-01-01"
+                ],
+                Sort::Priority
+            ),
+            ["Call mom", "Fix bike", "Pay bills", "Walk dog"]
+        );
+    }
+
+    #[test]
+    fn dates() {
+        let lines = [
+            "2024-01-01 Old",
+            "Undated",
+            "2024-02-01 New due:2024-03-01",
+            "2024-01-15 Middle due:2024-02-01",
+        ];
+
+        assert_eq!(
+            subjects(&lines, Sort::Due),
+            ["Middle", "New", "Old", "Undated"]
+        );
+        assert_eq!(
+            subjects(&lines, Sort::Created),
+            ["New", "Middle", "Old", "Undated"]
+        );
+    }
+
+    #[test]
+    fn manual() {
+        assert_eq!(
+            subjects(&["(A) b", "c", "(B) a"], Sort::Manual),
+            ["b", "c", "a"]
+        );
+        assert_eq!(
+            subjects(&["(A) b order:2", "c", "(B) a order:1"], Sort::Manual),
+            ["a", "b", "c"]
+        );
+    }
+
+    fn reordered(lines: &[&str], from: usize, to: usize) -> Vec<(String, String)> {
+        let tasks = lines
+            .iter()
+            .map(|x| Task::from(x.to_string()))
+            .collect::<Vec<_>>();
+
+        super::reorder(&tasks, from, to)
+            .into_iter()
+            .map(|(before, after)| (before.subject.clone(), after.tags[ORDER].clone()))
+            .collect()
+    }
+
+    #[test]
+    fn reorder() {
+        let change = |subject: &str, order: &str| (subject.to_string(), order.to_string());
+
+        assert_eq!(
+            reordered(&["a order:1", "b order:2", "c"], 2, 0),
+            [change("c", "0")]
+        );
+        assert_eq!(
+            reordered(&["a order:1", "b order:2", "c"], 0, 1),
+            [change("a", "3")]
+        );
+        assert_eq!(
+            reordered(&["a order:1", "b order:2", "c"], 0, 2),
+            [change("c", "3"), change("a", "4")]
+        );
+        assert_eq!(
+            reordered(&["a order:10", "b order:20", "c order:30"], 2, 1),
+            [change("c", "15")]
+        );
+        assert_eq!(
+            reordered(&["a order:10", "b order:11", "c order:12"], 2, 1),
+            [change("c", "11"), change("b", "12")]
+        );
+        assert_eq!(
+            reordered(&["a", "b", "c"], 0, 1),
+            [change("b", "1"), change("a", "2")]
+        );
+        assert!(reordered(&["a order:1", "b order:2", "c"], 0, 0).is_empty());
+    }
+
+    #[test]
+    fn parse() {
+        for sort in Sort::ALL {
+            assert_eq!(sort.name().parse(), Ok(sort));
+        }
+
+        assert!("size".parse::<Sort>().is_err());
+    }
+}
@@ -3,12 +3,16 @@ use relm4::ComponentController as _;
 
 #[derive(Debug)]
 pub enum MsgInput {
+    Notes(bool),
+    Page(String),
     Update,
     UpdateFilter(String),
 }
 
 pub struct Model {
-    query: String,
+    error: Option<String>,
+    notes: bool,
+    query: crate::query::parser::Expr,
     tasks: relm4::Controller<crate::widgets::tasks::Model>,
 }
 
@@ -17,29 +21,54 @@ impl Model {
         self.update();
     }
 
+    fn update_notes(&mut self, notes: bool) {
+        self.notes = notes;
+        self.update();
+    }
+
     fn update_filter(&mut self, filter: &str) {
-        self.query = filter.to_string();
+        match crate::query::parser::parse(filter) {
+            Ok(query) => {
+                self.query = query;
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err),
+        }
+
         self.update();
     }
 
     fn update(&self) {
-        let filter = self.query.to_lowercase();
+        if self.error.is_some() {
+            self.tasks
+                .emit(crate::widgets::tasks::Msg::Update(Vec::new()));
+            return;
+        }
+
         let list = crate::application::tasks();
+        let today = crate::date::today();
+
+        let tasks = if self.notes {
+            let notes = crate::application::notes();
+
+            crate::query::search(&list, &self.query, today, Some(&notes))
+        } else {
+            crate::query::search(&list, &self.query, today, None)
+        };
 
-        let tasks = list
-            .tasks
-            .iter()
-            .filter(|x| x.subject.to_lowercase().contains(filter.as_str()))
-            .cloned()
-            .collect();
+        if self.query.texts().is_empty() {
+            let tasks = tasks.into_iter().map(|x| x.task).collect();
 
-        self.tasks.emit(crate::widgets::tasks::Msg::Update(tasks));
+            self.tasks.emit(crate::widgets::tasks::Msg::Update(tasks));
+        } else {
+            self.tasks.emit(crate::widgets::tasks::Msg::Ranked(tasks));
+        }
     }
 }
 
 #[relm4::component(pub)]
 impl relm4::SimpleComponent for Model {
-    type Init = String;
+    type Init = (String, String);
     type Input = MsgInput;
     type Output = crate::widgets::task::MsgOutput;
 
@@ -50,11 +79,23 @@ impl relm4::SimpleComponent for Model {
     ) -> relm4::ComponentParts<Self> {
         use relm4::Component as _;
 
+        let (page, init) = init;
+
         let tasks = crate::widgets::tasks::Model::builder()
-            .launch(())
+            .launch(page)
             .forward(sender.output_sender(), std::convert::identity);
 
-        let model = Self { query: init, tasks };
+        let (query, error) = match crate::query::parser::parse(&init) {
+            Ok(query) => (query, None),
+            Err(err) => (Default::default(), Some(err)),
+        };
+
+        let model = Self {
+            error,
+            notes: false,
+            query,
+            tasks,
+        };
 
         let widgets = view_output!();
 
@@ -65,6 +106,8 @@ impl relm4::SimpleComponent for Model {
         use MsgInput::*;
 
         match msg {
+            Notes(notes) => self.update_notes(notes),
+            Page(page) => self.tasks.emit(crate::widgets::tasks::Msg::Page(page)),
             Update => self.update_tasks(),
             UpdateFilter(filter) => self.update_filter(&filter),
         }
@@ -72,6 +115,24 @@ impl relm4::SimpleComponent for Model {
 
     view! {
         gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+
+            gtk::CheckButton {
+                set_label: Some("Search in notes"),
+                set_active: model.notes,
+
+                connect_toggled[sender] => move |button| {
+                    sender.input(MsgInput::Notes(button.is_active()));
+                },
+            },
+            gtk::Label {
+                add_css_class: "error",
+                set_xalign: 0.,
+                #[watch]
+                set_visible: model.error.is_some(),
+                #[watch]
+                set_text: model.error.as_deref().unwrap_or_default(),
+            },
             append: model.tasks.widget(),
         }
     }
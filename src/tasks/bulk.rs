@@ -0,0 +1,184 @@
+use super::history::Command;
+use super::Task;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    Complete,
+    Priority(u8),
+    ShiftPriority(i8),
+    AddProject(String),
+    RemoveProject(String),
+    AddContext(String),
+    RemoveContext(String),
+    Due(Option<chrono::NaiveDate>),
+    ShiftDue(i64),
+    Defer(Option<chrono::NaiveDate>),
+    Flag(bool),
+    Delete,
+}
+
+impl Action {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Complete => "Tasks done",
+            Self::Priority(_) | Self::ShiftPriority(_) => "Priority changed",
+            Self::AddProject(_) | Self::AddContext(_) => "Tags added",
+            Self::RemoveProject(_) | Self::RemoveContext(_) => "Tags removed",
+            Self::Due(_) | Self::ShiftDue(_) => "Due date changed",
+            Self::Defer(_) => "Tasks deferred",
+            Self::Flag(true) => "Tasks flagged",
+            Self::Flag(false) => "Tasks unflagged",
+            Self::Delete => "Tasks deleted",
+        }
+    }
+
+    pub fn command(&self, tasks: &[Task]) -> Command {
+        let mut command = Command::new(self.label());
+
+        for task in tasks {
+            match self {
+                Self::Complete => {
+                    if !task.finished {
+                        command = command.merge(Command::complete(task));
+                    }
+                }
+                Self::Delete => command = command.delete(task.clone()),
+                _ => {
+                    let mut after = task.clone();
+                    self.apply(&mut after);
+
+                    if after.to_string() != task.to_string() {
+                        command = command.edit(task.clone(), after);
+                    }
+                }
+            }
+        }
+
+        command
+    }
+
+    fn apply(&self, task: &mut Task) {
+        match self {
+            Self::Complete | Self::Delete => (),
+            Self::Priority(priority) => task.priority = (*priority).into(),
+            Self::ShiftPriority(n) => {
+                if task.priority.is_lowest() {
+                    if *n < 0 {
+                        task.priority = 0.into();
+                    }
+                } else {
+                    let priority = u8::from(task.priority.clone()) as i8;
+
+                    task.priority = ((priority + n).clamp(0, 26) as u8).into();
+                }
+            }
+            Self::AddProject(name) => add_tag(task, '+', name),
+            Self::RemoveProject(name) => remove_tag(task, '+', name),
+            Self::AddContext(name) => add_tag(task, '@', name),
+            Self::RemoveContext(name) => remove_tag(task, '@', name),
+            Self::Due(date) => task.due_date = *date,
+            Self::ShiftDue(days) => {
+                task.due_date = task
+                    .due_date
+                    .and_then(|x| x.checked_add_signed(chrono::Duration::days(*days)));
+            }
+            Self::Defer(date) => task.threshold_date = *date,
+            Self::Flag(flagged) => task.flagged = *flagged,
+        }
+    }
+}
+
+fn tags(task: &mut Task, prefix: char) -> &mut Vec<String> {
+    if prefix == '+' {
+        &mut task.projects
+    } else {
+        &mut task.contexts
+    }
+}
+
+fn add_tag(task: &mut Task, prefix: char, name: &str) {
+    if tags(task, prefix).iter().any(|x| x == name) {
+        return;
+    }
+
+    task.subject = format!("{} {prefix}{name}", task.subject);
+    tags(task, prefix).push(name.to_string());
+}
+
+fn remove_tag(task: &mut Task, prefix: char, name: &str) {
+    let tag = format!("{prefix}{name}");
+
+    task.subject = task
+        .subject
+        .split_whitespace()
+        .filter(|x| *x != tag)
+        .collect::<Vec<_>>()
+        .join(" ");
+    tags(task, prefix).retain(|x| x != name);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tasks::bulk::*;
+
+    fn applied(action: Action, line: &str) -> String {
+        let mut task = Task::from(line.to_string());
+        action.apply(&mut task);
+
+        task.to_string()
+    }
+
+    #[test]
+    fn apply() {
+        assert_eq!(
+            applied(Action::AddProject("home".to_string()), "Fix bike"),
+            "Fix bike +home"
+        );
+        assert_eq!(
+            applied(Action::AddContext("phone".to_string()), "Call @phone"),
+            "Call @phone"
+        );
+        assert_eq!(
+            applied(
+                Action::RemoveProject("home".to_string()),
+                "Fix +home bike +homework"
+            ),
+            "Fix bike +homework"
+        );
+        assert_eq!(
+            applied(Action::ShiftDue(7), "Pay bills due:2024-01-28"),
+            "Pay bills due:2024-02-04"
+        );
+        assert_eq!(applied(Action::ShiftDue(7), "Walk dog"), "Walk dog");
+        assert_eq!(applied(Action::Priority(0), "Walk dog"), "(A) Walk dog");
+        assert_eq!(
+            applied(Action::ShiftPriority(-1), "(B) Walk dog"),
+            "(A) Walk dog"
+        );
+        assert_eq!(
+            applied(Action::ShiftPriority(-1), "(A) Walk dog"),
+            "(A) Walk dog"
+        );
+        assert_eq!(
+            applied(Action::ShiftPriority(1), "(Z) Walk dog"),
+            "Walk dog"
+        );
+        assert_eq!(
+            applied(Action::ShiftPriority(-1), "Walk dog"),
+            "(A) Walk dog"
+        );
+        assert_eq!(applied(Action::ShiftPriority(1), "Walk dog"), "Walk dog");
+    }
+
+    #[test]
+    fn command() {
+        let tasks = ["x Fix bike", "(A) Walk dog"]
+            .map(|x| Task::from(x.to_string()))
+            .to_vec();
+
+        assert!(Action::Complete.command(&tasks[..1]).is_empty());
+        assert!(!Action::Complete.command(&tasks).is_empty());
+        assert!(Action::Priority(0).command(&tasks[1..]).is_empty());
+        assert!(!Action::Delete.command(&tasks).is_empty());
+    }
+}
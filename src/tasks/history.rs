@@ -0,0 +1,456 @@
+#[derive(Clone, Debug)]
+pub struct Change {
+    before: Option<super::Task>,
+    after: Option<super::Task>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Command {
+    pub label: String,
+    id: usize,
+    changes: Vec<Change>,
+}
+
+impl Command {
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            id: 0,
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn complete(task: &super::Task) -> Self {
+        let mut t = task.clone();
+
+        if t.finished {
+            t.uncomplete();
+            t.archived = false;
+        } else {
+            t.complete();
+        }
+
+        let label = if t.finished {
+            "Task done"
+        } else {
+            "Task undone"
+        };
+        let mut command = Self::new(label).edit(task.clone(), t.clone());
+
+        if t.finished {
+            if let Some(ref recurrence) = t.recurrence {
+                let due = if recurrence.strict && t.due_date.is_some() {
+                    t.due_date.unwrap()
+                } else {
+                    crate::date::today()
+                };
+
+                let mut new: super::Task = t.clone();
+                new.uncomplete();
+                new.create_date = Some(crate::date::today());
+                new.due_date = Some(recurrence.clone() + due);
+
+                if let Some(threshold_date) = t.threshold_date {
+                    new.threshold_date = Some(recurrence.clone() + threshold_date);
+                }
+
+                command = command.add(new);
+            }
+        }
+
+        command
+    }
+
+    pub fn duplicate(task: &super::Task, notes_dir: &std::path::Path) -> Result<Self, String> {
+        let mut new = task.clone();
+        new.create_date = Some(crate::date::today());
+        new.tags.remove("id");
+
+        if let Some(content) = task.note.content() {
+            new.note = content.into();
+            new.write_note(notes_dir)
+                .map_err(|err| format!("Unable to save note: {err}"))?;
+        }
+
+        let mut copy = super::Task::from(new.to_string());
+        copy.note = new.note.clone();
+        copy.archived = task.archived;
+        copy.workspace = task.workspace.clone();
+
+        Ok(Self::new("Task duplicated").add(copy))
+    }
+
+    pub fn add(mut self, task: super::Task) -> Self {
+        self.changes.push(Change {
+            before: None,
+            after: Some(task),
+        });
+
+        self
+    }
+
+    pub fn edit(mut self, before: super::Task, after: super::Task) -> Self {
+        self.changes.push(Change {
+            before: Some(before),
+            after: Some(after),
+        });
+
+        self
+    }
+
+    pub fn delete(mut self, task: super::Task) -> Self {
+        self.changes.push(Change {
+            before: Some(task),
+            after: None,
+        });
+
+        self
+    }
+
+    pub fn merge(mut self, other: Self) -> Self {
+        self.changes.extend(other.changes);
+
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    fn inverse(&self) -> Self {
+        let changes = self
+            .changes
+            .iter()
+            .rev()
+            .map(|x| Change {
+                before: x.after.clone(),
+                after: x.before.clone(),
+            })
+            .collect();
+
+        Self {
+            label: self.label.clone(),
+            id: self.id,
+            changes,
+        }
+    }
+
+    fn run(&self, list: &mut super::List) -> Result<(), String> {
+        self.apply(list)?;
+        list.write()?;
+
+        for change in &self.changes {
+            if let (Some(before), None) = (&change.before, &change.after) {
+                if let Some(path) = before.note_path(&list.notes_dir) {
+                    if let Err(err) = std::fs::remove_file(&path) {
+                        log::warn!("Unable to delete note {}: {err}", path.display());
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn apply(&self, list: &mut super::List) -> Result<(), String> {
+        for change in &self.changes {
+            let position = match change.before {
+                Some(ref before) => Some(
+                    Self::position(list, before)
+                        .ok_or_else(|| format!("Task '{before}' modified by another program"))?,
+                ),
+                None => None,
+            };
+
+            match (position, change.after.clone()) {
+                (Some(position), Some(mut after)) => {
+                    after.inherit(&list.tasks[position]);
+                    after.id = position;
+                    list.tasks[position] = after;
+                }
+                (Some(position), None) => {
+                    list.tasks.remove(position);
+                }
+                (None, Some(after)) => list.append(after),
+                (None, None) => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn position(list: &super::List, task: &super::Task) -> Option<usize> {
+        let mut written = super::Task::from(task.to_string());
+        written.id = task.id;
+
+        list.find(&written).or_else(|| list.find(task))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Command>,
+    redo: Vec<Command>,
+    next: usize,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn execute(
+        &mut self,
+        list: &mut super::List,
+        mut command: Command,
+    ) -> Result<usize, String> {
+        command.run(list)?;
+
+        self.next += 1;
+        command.id = self.next;
+
+        self.undo.push(command);
+        self.redo.clear();
+
+        Ok(self.next)
+    }
+
+    pub fn last(&self) -> Option<usize> {
+        self.undo.last().map(|x| x.id)
+    }
+
+    pub fn undo_command(
+        &mut self,
+        list: &mut super::List,
+        id: usize,
+    ) -> Result<Option<String>, String> {
+        match self.undo.last() {
+            Some(command) if command.id == id => self.undo(list),
+            Some(command) => Err(format!(
+                "'{}' was done afterwards, undo it first",
+                command.label
+            )),
+            None => Ok(None),
+        }
+    }
+
+    pub fn undo(&mut self, list: &mut super::List) -> Result<Option<String>, String> {
+        let Some(command) = self.undo.pop() else {
+            return Ok(None);
+        };
+
+        if let Err(err) = command.inverse().run(list) {
+            self.undo.push(command);
+            return Err(err);
+        }
+
+        let label = command.label.clone();
+        self.redo.push(command);
+
+        Ok(Some(label))
+    }
+
+    pub fn redo(&mut self, list: &mut super::List) -> Result<Option<String>, String> {
+        let Some(command) = self.redo.pop() else {
+            return Ok(None);
+        };
+
+        if let Err(err) = command.run(list) {
+            self.redo.push(command);
+            return Err(err);
+        }
+
+        let label = command.label.clone();
+        self.undo.push(command);
+
+        Ok(Some(label))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tasks::history::*;
+
+    fn list(name: &str, todo: &str) -> (std::path::PathBuf, crate::tasks::List) {
+        let dir = std::env::temp_dir().join(format!("effitask-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let done = dir.join("done.txt").to_string_lossy().to_string();
+        let path = dir.join("todo.txt").to_string_lossy().to_string();
+        std::fs::write(&path, todo).unwrap();
+        std::fs::write(&done, "").unwrap();
+
+        (dir, crate::tasks::List::from_files(&path, &done))
+    }
+
+    fn subjects(list: &mut crate::tasks::List) -> Vec<String> {
+        list.reload();
+        list.iter().map(|x| x.subject.clone()).collect()
+    }
+
+    #[test]
+    fn execute_undo_redo() {
+        let (dir, mut list) = list("history", "Call mom\nPay bills\n");
+        let mut history = History::new();
+
+        let mut after = list.tasks[0].clone();
+        after.subject = "Call dad".to_string();
+        let command = Command::new("Task updated").edit(list.tasks[0].clone(), after);
+
+        let first = history.execute(&mut list, command).unwrap();
+        assert_eq!(subjects(&mut list), ["Call dad", "Pay bills"]);
+
+        let command = Command::new("Task deleted").delete(list.tasks[1].clone());
+        let second = history.execute(&mut list, command).unwrap();
+        assert_eq!(subjects(&mut list), ["Call dad"]);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("todo.txt")).unwrap(),
+            "Call dad\n"
+        );
+
+        assert!(history.undo_command(&mut list, first).is_err());
+        assert_eq!(
+            history.undo_command(&mut list, second),
+            Ok(Some("Task deleted".to_string()))
+        );
+        assert_eq!(subjects(&mut list), ["Call dad", "Pay bills"]);
+
+        assert_eq!(
+            history.undo(&mut list),
+            Ok(Some("Task updated".to_string()))
+        );
+        assert_eq!(subjects(&mut list), ["Call mom", "Pay bills"]);
+        assert_eq!(history.undo(&mut list), Ok(None));
+
+        assert_eq!(
+            history.redo(&mut list),
+            Ok(Some("Task updated".to_string()))
+        );
+        assert_eq!(subjects(&mut list), ["Call dad", "Pay bills"]);
+
+        let command =
+            Command::new("Task created").add(crate::tasks::Task::from("Buy milk".to_string()));
+        history.execute(&mut list, command).unwrap();
+        assert_eq!(history.redo(&mut list), Ok(None));
+        assert_eq!(subjects(&mut list), ["Call dad", "Pay bills", "Buy milk"]);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn delete_note() {
+        let (dir, mut list) = list("note", "Call mom note:mom.txt\n");
+        let note = dir.join("mom.txt");
+        std::fs::write(&note, "Ask about the trip").unwrap();
+        list.notes_dir = dir.clone();
+        list.tasks[0].note = todo_txt::task::Note::Long {
+            filename: "mom.txt".to_string(),
+            content: "Ask about the trip".to_string(),
+        };
+
+        let mut history = History::new();
+        let command = Command::new("Task deleted").delete(list.tasks[0].clone());
+
+        history.execute(&mut list, command).unwrap();
+        assert!(!note.exists());
+
+        history.undo(&mut list).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&note).unwrap(),
+            "Ask about the trip"
+        );
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn duplicate_note() {
+        let (dir, mut list) = list("duplicate", "Call mom note:mom.txt\n");
+        std::fs::write(dir.join("mom.txt"), "Ask about the trip").unwrap();
+        list.notes_dir = dir.clone();
+        list.tasks[0].note = todo_txt::task::Note::Long {
+            filename: "mom.txt".to_string(),
+            content: "Ask about the trip".to_string(),
+        };
+
+        let mut history = History::new();
+        let command = Command::duplicate(&list.tasks[0], &list.notes_dir).unwrap();
+        let copy = command.changes[0].after.clone().unwrap();
+        let note = copy.note_path(&dir).unwrap();
+
+        assert_ne!(copy.fingerprint(), list.tasks[0].fingerprint());
+        assert_eq!(
+            std::fs::read_to_string(&note).unwrap(),
+            "Ask about the trip"
+        );
+
+        history.execute(&mut list, command).unwrap();
+        assert_eq!(list.tasks.len(), 2);
+
+        history.undo(&mut list).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("todo.txt")).unwrap(),
+            "Call mom note:mom.txt\n"
+        );
+        assert!(!note.exists());
+        assert!(dir.join("mom.txt").exists());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn inverse() {
+        let task = crate::tasks::Task::from("Call mom".to_string());
+        let mut edited = task.clone();
+        edited.subject = "Call dad".to_string();
+
+        let command = Command::new("Task created").add(task.clone());
+        let inverse = command.inverse();
+        assert_eq!(inverse.changes[0].before, Some(task.clone()));
+        assert_eq!(inverse.changes[0].after, None);
+
+        let command = Command::new("Task deleted").delete(task.clone());
+        let inverse = command.inverse();
+        assert_eq!(inverse.changes[0].before, None);
+        assert_eq!(inverse.changes[0].after, Some(task.clone()));
+
+        let command = Command::new("Task updated").edit(task.clone(), edited.clone());
+        let inverse = command.inverse();
+        assert_eq!(inverse.changes[0].before, Some(edited));
+        assert_eq!(inverse.changes[0].after, Some(task));
+    }
+
+    #[test]
+    fn uncomplete() {
+        let mut task = crate::tasks::Task::from("x 2024-01-01 Call mom".to_string());
+        task.archived = true;
+
+        let command = Command::complete(&task);
+        let after = command.changes[0].after.as_ref().unwrap();
+
+        assert!(!after.finished);
+        assert!(!after.archived);
+    }
+
+    #[test]
+    fn inverse_recurrence() {
+        let task = crate::tasks::Task::from("Water plants due:2024-01-01 rec:+1w".to_string());
+
+        let command = Command::complete(&task);
+        assert_eq!(command.changes.len(), 2);
+
+        let inverse = command.inverse();
+        assert_eq!(inverse.changes.len(), 2);
+
+        let new = inverse.changes[0].before.as_ref().unwrap();
+        assert_eq!(inverse.changes[0].after, None);
+        assert!(!new.finished);
+        assert_eq!(
+            new.due_date,
+            Some(chrono::NaiveDate::from_ymd_opt(2024, 1, 8).unwrap())
+        );
+
+        assert!(inverse.changes[1].before.as_ref().unwrap().finished);
+        assert_eq!(inverse.changes[1].after, Some(task));
+    }
+}
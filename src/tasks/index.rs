@@ -0,0 +1,174 @@
+use std::collections::{HashMap, HashSet};
+
+const GRAM: usize = 3;
+
+#[derive(Clone, Debug, Default)]
+pub struct Index {
+    notes: HashMap<String, String>,
+    grams: HashMap<String, HashSet<String>>,
+}
+
+impl Index {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, list: &super::List) {
+        let mut seen = HashSet::new();
+
+        for task in &list.tasks {
+            let todo_txt::task::Note::Long { ref filename, .. } = task.note else {
+                continue;
+            };
+
+            if !seen.insert(filename.clone()) {
+                continue;
+            }
+
+            let content = task.note.content().unwrap_or_default().to_lowercase();
+
+            if self.notes.get(filename) == Some(&content) {
+                continue;
+            }
+
+            self.remove(filename);
+
+            for gram in Self::grams(&content) {
+                self.grams.entry(gram).or_default().insert(filename.clone());
+            }
+
+            self.notes.insert(filename.clone(), content);
+        }
+
+        let removed = self
+            .notes
+            .keys()
+            .filter(|x| !seen.contains(*x))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for filename in removed {
+            self.remove(&filename);
+        }
+    }
+
+    fn remove(&mut self, filename: &str) {
+        let Some(content) = self.notes.remove(filename) else {
+            return;
+        };
+
+        for gram in Self::grams(&content) {
+            if let Some(filenames) = self.grams.get_mut(&gram) {
+                filenames.remove(filename);
+
+                if filenames.is_empty() {
+                    self.grams.remove(&gram);
+                }
+            }
+        }
+    }
+
+    fn words(content: &str) -> impl Iterator<Item = &str> {
+        content
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|x| !x.is_empty())
+    }
+
+    fn grams(content: &str) -> HashSet<String> {
+        let mut grams = HashSet::new();
+
+        for word in Self::words(content) {
+            let chars = word.chars().collect::<Vec<_>>();
+
+            for n in 1..=GRAM.min(chars.len()) {
+                grams.extend(chars.windows(n).map(|x| x.iter().collect::<String>()));
+            }
+        }
+
+        grams
+    }
+
+    fn query_grams(text: &str) -> Vec<String> {
+        Self::words(text)
+            .flat_map(|word| {
+                let chars = word.chars().collect::<Vec<_>>();
+
+                chars
+                    .windows(GRAM.min(chars.len()))
+                    .map(|x| x.iter().collect::<String>())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    pub fn find(&self, text: &str) -> HashSet<String> {
+        let text = text.to_lowercase();
+        let grams = Self::query_grams(&text);
+
+        if grams.is_empty() {
+            return self
+                .notes
+                .iter()
+                .filter(|(_, content)| content.contains(&text))
+                .map(|(filename, _)| filename.clone())
+                .collect();
+        }
+
+        let Some(mut postings) = grams
+            .iter()
+            .map(|x| self.grams.get(x))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return HashSet::new();
+        };
+
+        postings.sort_by_key(|x| x.len());
+
+        postings[0]
+            .iter()
+            .filter(|x| postings[1..].iter().all(|y| y.contains(*x)))
+            .filter(|x| self.notes[*x].contains(&text))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tasks::index::*;
+
+    fn task(subject: &str, filename: &str, content: &str) -> crate::tasks::Task {
+        let mut task = crate::tasks::Task::from(subject.to_string());
+        task.note = todo_txt::task::Note::Long {
+            filename: filename.to_string(),
+            content: content.to_string(),
+        };
+
+        task
+    }
+
+    #[test]
+    fn find() {
+        let mut list = crate::tasks::List::new();
+        list.append(task("Shopping", "a.md", "# Groceries\n\nBuy Milk and eggs"));
+        list.append(task("Fix sink", "b.md", "Call the plumber"));
+
+        let mut index = Index::new();
+        index.update(&list);
+
+        assert_eq!(index.notes.len(), 2);
+        assert_eq!(index.find("milk"), HashSet::from(["a.md".to_string()]));
+        assert_eq!(index.find("ilk and"), HashSet::from(["a.md".to_string()]));
+        assert_eq!(index.find("K"), HashSet::from(["a.md".to_string()]));
+        assert_eq!(index.find("the plumb"), HashSet::from(["b.md".to_string()]));
+        assert!(index.find("milk eggs").is_empty());
+        assert!(index.find("bread").is_empty());
+
+        list.tasks.remove(0);
+        index.update(&list);
+
+        assert_eq!(index.notes.len(), 1);
+        assert!(index.find("milk").is_empty());
+        assert!(!index.grams.contains_key("mil"));
+    }
+}
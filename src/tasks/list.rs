@@ -1,63 +1,254 @@
+#[derive(Clone, Debug, Default)]
+struct File {
+    path: String,
+    hash: Option<u64>,
+    lines: Vec<String>,
+    newline: &'static str,
+}
+
+impl File {
+    fn new(path: &str, content: Option<&str>) -> Self {
+        let newline = if content.is_some_and(|x| x.contains("\r\n")) {
+            "\r\n"
+        } else {
+            "\n"
+        };
+
+        Self {
+            path: path.to_string(),
+            hash: content.map(|x| Self::hash(x.as_bytes())),
+            lines: content
+                .unwrap_or_default()
+                .lines()
+                .map(str::to_string)
+                .collect(),
+            newline,
+        }
+    }
+
+    fn hash(content: &[u8]) -> u64 {
+        use std::hash::{Hash as _, Hasher as _};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        content.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    fn has_changed(&self) -> bool {
+        let current = std::fs::read(&self.path).ok();
+
+        current.as_deref().map(Self::hash) != self.hash
+    }
+
+    fn is_task(line: &str) -> bool {
+        let line = line.trim();
+
+        !line.is_empty() && line != "#" && !line.starts_with("# ")
+    }
+
+    fn render(&self, tasks: Vec<crate::tasks::Task>) -> (String, Vec<crate::tasks::Task>) {
+        let mut tasks: Vec<_> = tasks.into_iter().map(Some).collect();
+        let mut content = String::new();
+        let mut rendered = Vec::new();
+
+        for line in &self.lines {
+            if !Self::is_task(line) {
+                content.push_str(line);
+                content.push_str(self.newline);
+                continue;
+            }
+
+            let original = crate::tasks::Task::from(line.clone());
+            let Some(mut task) = tasks
+                .iter_mut()
+                .find(|x| {
+                    x.as_ref()
+                        .is_some_and(|x| x.fingerprint() == original.fingerprint())
+                })
+                .and_then(Option::take)
+            else {
+                continue;
+            };
+
+            if task.to_string() == original.to_string() {
+                content.push_str(line);
+            } else {
+                task.name_note();
+                content.push_str(&task.to_string());
+                rendered.push(task);
+            }
+            content.push_str(self.newline);
+        }
+
+        for mut task in tasks.into_iter().flatten() {
+            task.name_note();
+            content.push_str(&task.to_string());
+            content.push_str(self.newline);
+            rendered.push(task);
+        }
+
+        (content, rendered)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct Source {
+    name: Option<String>,
+    todo: File,
+    done: File,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct List {
     pub inner: todo_txt::task::List<super::Task>,
-    todo: String,
-    done: String,
+    sources: Vec<Source>,
+    auto_archive: bool,
+    pub notes_dir: std::path::PathBuf,
 }
 
 impl List {
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            auto_archive: true,
+            notes_dir: crate::tasks::Task::notes_dir(),
+            ..Self::default()
+        }
     }
 
     pub fn from_files(todo: &str, done: &str) -> Self {
         let mut list = Self::new();
 
-        list.load_todo(todo);
-        list.load_done(done);
+        list.load(None, todo, done);
 
         list
     }
 
-    fn load_todo(&mut self, todo: &str) {
-        let tasks = self.load_file(todo);
+    pub fn load(&mut self, name: Option<&str>, todo: &str, done: &str) {
+        let (todo, tasks) = Self::load_file(name, todo, false);
+        self.inner.extend(tasks);
 
-        self.todo = todo.to_string();
+        let (done, tasks) = Self::load_file(name, done, true);
         self.inner.extend(tasks);
+
+        self.sources.push(Source {
+            name: name.map(str::to_string),
+            todo,
+            done,
+        });
+
+        self.renumber();
     }
 
-    fn load_done(&mut self, done: &str) {
-        let tasks = self.load_file(done);
+    pub fn reload(&mut self) {
+        let sources = std::mem::take(&mut self.sources);
 
-        self.done = done.to_string();
-        self.inner.extend(tasks);
+        *self = Self {
+            auto_archive: self.auto_archive,
+            notes_dir: std::mem::take(&mut self.notes_dir),
+            ..Self::default()
+        };
+
+        for source in sources {
+            self.load(source.name.as_deref(), &source.todo.path, &source.done.path);
+        }
     }
 
-    fn load_file(&self, path: &str) -> Vec<crate::tasks::Task> {
-        use std::io::BufRead;
+    pub fn refresh(&mut self) -> bool {
+        if !self.has_changed() {
+            return false;
+        }
 
-        let mut tasks = Vec::new();
-        let Ok(file) = std::fs::File::open(path) else {
-            log::error!("Unable to open {path:?}");
+        let mut old = std::mem::take(&mut self.inner.tasks);
 
-            return tasks;
-        };
+        for source in &mut self.sources {
+            let name = source.name.clone();
 
-        let last_id = self.inner.len();
+            for (file, archived) in [(&mut source.todo, false), (&mut source.done, true)] {
+                let (mut tasks, others): (Vec<_>, Vec<_>) = old
+                    .into_iter()
+                    .partition(|x| x.workspace == name && x.archived == archived);
+                old = others;
 
-        for (id, line) in std::io::BufReader::new(file).lines().enumerate() {
-            let line = line.unwrap();
+                if file.has_changed() {
+                    (*file, tasks) = Self::load_file(name.as_deref(), &file.path, archived);
+                }
 
-            if line.is_empty() {
-                continue;
+                self.inner.extend(tasks);
+            }
+        }
+
+        self.renumber();
+
+        true
+    }
+
+    fn renumber(&mut self) {
+        for (id, task) in self.inner.tasks.iter_mut().enumerate() {
+            task.id = id;
+        }
+    }
+
+    pub fn has_changed(&self) -> bool {
+        self.sources
+            .iter()
+            .any(|x| x.todo.has_changed() || x.done.has_changed())
+    }
+
+    pub fn notes_changed(&self) -> bool {
+        self.inner.iter().any(|task| match task.note {
+            todo_txt::task::Note::Long { ref content, .. } => {
+                let current = task
+                    .note_path(&self.notes_dir)
+                    .and_then(|x| std::fs::read(x).ok())
+                    .map(|x| File::hash(&x));
+
+                current != Some(File::hash(content.as_bytes()))
             }
+            _ => false,
+        })
+    }
+
+    pub fn set_auto_archive(&mut self, auto_archive: bool) {
+        self.auto_archive = auto_archive;
+    }
 
-            let mut task = crate::tasks::Task::from(line);
-            task.id = last_id + id;
+    fn load_file(
+        name: Option<&str>,
+        path: &str,
+        archived: bool,
+    ) -> (File, Vec<crate::tasks::Task>) {
+        let content = std::fs::read_to_string(path);
+
+        if content.is_err() {
+            log::error!("Unable to open {path:?}");
+        }
+
+        let file = File::new(path, content.ok().as_deref());
+        let mut tasks = Vec::new();
+
+        for line in file.lines.iter().filter(|x| File::is_task(x)) {
+            let mut task = crate::tasks::Task::from(line.clone());
+            task.archived = archived;
+            task.workspace = name.map(str::to_string);
             tasks.push(task);
         }
 
-        tasks
+        (file, tasks)
+    }
+
+    fn source(&self, task: &crate::tasks::Task) -> usize {
+        self.sources
+            .iter()
+            .position(|x| x.name == task.workspace)
+            .unwrap_or(0)
+    }
+
+    pub fn find(&self, task: &crate::tasks::Task) -> Option<usize> {
+        match self.inner.tasks.get(task.id) {
+            Some(x) if x.is(task) => Some(task.id),
+            _ => self.inner.tasks.iter().position(|x| x.is(task)),
+        }
     }
 
     pub fn projects(&self) -> Vec<String> {
@@ -84,42 +275,155 @@ impl List {
             .contexts()
     }
 
-    pub fn write(&self) -> Result<(), String> {
-        let todo = self.inner.iter().filter(|x| !x.finished).cloned().collect();
-        self.write_tasks(&self.todo, todo)?;
+    pub fn write(&mut self) -> Result<(), String> {
+        let auto_archive = self.auto_archive;
+        let mut files = Vec::new();
+
+        for (n, source) in self.sources.iter().enumerate() {
+            let (done, todo): (Vec<_>, Vec<_>) = self
+                .inner
+                .iter()
+                .filter(|x| self.source(x) == n)
+                .cloned()
+                .partition(|x| x.finished && (auto_archive || x.archived));
+
+            for (archived, file, tasks) in [(false, &source.todo, todo), (true, &source.done, done)]
+            {
+                let (content, rendered) = file.render(tasks);
+
+                if Some(File::hash(content.as_bytes())) != file.hash {
+                    files.push((n, archived, content, rendered));
+                }
+            }
+        }
+
+        for &(n, archived, ..) in &files {
+            let file = self.file(n, archived);
+
+            if file.has_changed() {
+                return Err(format!("{} modified by another program", file.path));
+            }
+        }
 
-        let done = self.inner.iter().filter(|x| x.finished).cloned().collect();
-        self.write_tasks(&self.done, done)?;
+        for task in files.iter_mut().flat_map(|x| &mut x.3) {
+            task.write_note(&self.notes_dir)
+                .map_err(|err| format!("Unable to save note: {err}"))?;
+        }
+
+        let mut staged = Vec::new();
+
+        for (n, archived, content, _) in files {
+            let path = self.file(n, archived).path.clone();
+            let tmp = Self::backup(&path).and_then(|_| {
+                Self::stage(&Self::resolve(&path), content.as_bytes())
+                    .map_err(|err| format!("Unable to write tasks in {path}: {err}"))
+            });
+
+            match tmp {
+                Ok(tmp) => staged.push((n, archived, content, tmp)),
+                Err(err) => {
+                    for (.., tmp) in staged {
+                        std::fs::remove_file(tmp).ok();
+                    }
+                    return Err(err);
+                }
+            }
+        }
+
+        for (i, (n, archived, content, tmp)) in staged.iter().enumerate() {
+            let path = self.file(*n, *archived).path.clone();
+
+            if let Err(err) = Self::commit(tmp, &Self::resolve(&path)) {
+                for (.., tmp) in &staged[i..] {
+                    std::fs::remove_file(tmp).ok();
+                }
+                return Err(format!(
+                    "Unable to write tasks in {path}, tasks are partially saved: {err}"
+                ));
+            }
+
+            *self.file_mut(*n, *archived) = File::new(&path, Some(content));
+        }
 
         Ok(())
     }
 
-    fn write_tasks(&self, file: &str, tasks: Vec<crate::tasks::Task>) -> Result<(), String> {
-        use std::io::Write;
+    fn file(&self, n: usize, archived: bool) -> &File {
+        if archived {
+            &self.sources[n].done
+        } else {
+            &self.sources[n].todo
+        }
+    }
+
+    fn file_mut(&mut self, n: usize, archived: bool) -> &mut File {
+        if archived {
+            &mut self.sources[n].done
+        } else {
+            &mut self.sources[n].todo
+        }
+    }
 
-        self.backup(file)?;
+    fn resolve(file: &str) -> std::path::PathBuf {
+        std::fs::canonicalize(file).unwrap_or_else(|_| file.into())
+    }
 
-        let mut f = match std::fs::File::create(file) {
-            Ok(f) => f,
-            Err(err) => return Err(format!("Unable to write tasks: {err}")),
-        };
+    pub fn atomic_write(file: &str, content: &[u8]) -> std::io::Result<()> {
+        let path = Self::resolve(file);
+        let tmp = Self::stage(&path, content)?;
+
+        Self::commit(&tmp, &path).inspect_err(|_| {
+            std::fs::remove_file(&tmp).ok();
+        })
+    }
+
+    fn stage(path: &std::path::Path, content: &[u8]) -> std::io::Result<std::path::PathBuf> {
+        use std::io::Write as _;
+
+        let name = path
+            .file_name()
+            .map(|x| x.to_string_lossy())
+            .unwrap_or_default();
+        let tmp = Self::dir(path).join(format!(".{name}.{}.tmp", std::process::id()));
 
-        for mut task in tasks {
-            if let Err(err) = task.note.write() {
-                log::error!("Unable to save note: {err}");
-                task.note = todo_txt::task::Note::None;
+        let result = (|| -> std::io::Result<()> {
+            let mut f = std::fs::File::create(&tmp)?;
+            f.write_all(content)?;
+
+            if let Ok(metadata) = std::fs::metadata(path) {
+                f.set_permissions(metadata.permissions())?;
             }
 
-            match f.write_all(format!("{task}\n").as_bytes()) {
-                Ok(_) => (),
-                Err(err) => log::error!("Unable to write tasks: {err}"),
-            };
+            f.sync_all()
+        })();
+
+        match result {
+            Ok(()) => Ok(tmp),
+            Err(err) => {
+                std::fs::remove_file(&tmp).ok();
+                Err(err)
+            }
+        }
+    }
+
+    fn commit(tmp: &std::path::Path, path: &std::path::Path) -> std::io::Result<()> {
+        std::fs::rename(tmp, path)?;
+
+        if let Ok(dir) = std::fs::File::open(Self::dir(path)) {
+            dir.sync_all().ok();
         }
 
         Ok(())
     }
 
-    fn backup(&self, file: &str) -> Result<(), String> {
+    fn dir(path: &std::path::Path) -> &std::path::Path {
+        match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => std::path::Path::new("."),
+        }
+    }
+
+    fn backup(file: &str) -> Result<(), String> {
         let bak = format!("{file}.bak");
 
         match std::fs::copy(file, bak) {
@@ -128,18 +432,6 @@ impl List {
         }
     }
 
-    pub fn add(&mut self, text: &str) -> Result<(), String> {
-        use std::str::FromStr as _;
-
-        let mut task = crate::tasks::Task::from_str(text)
-            .map_err(|_| format!("Unable to convert task: '{text}'"))?;
-
-        task.create_date = Some(crate::date::today());
-
-        self.append(task);
-        self.write()
-    }
-
     pub fn append(&mut self, task: crate::tasks::Task) {
         self.inner.push(task);
     }
@@ -158,3 +450,173 @@ impl std::ops::DerefMut for List {
         &mut self.inner
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::tasks::list::*;
+
+    #[test]
+    fn round_trip() {
+        let content = "# Work\n(A)   Call  mom\n\nx 2024-01-01 Pay bills\n";
+        let file = File::new("todo.txt", Some(content));
+        let tasks: Vec<_> = file
+            .lines
+            .iter()
+            .filter(|x| File::is_task(x))
+            .map(|x| crate::tasks::Task::from(x.clone()))
+            .collect();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(file.render(tasks.clone()).0, content);
+
+        let mut edited = tasks;
+        edited[1].subject = "Pay taxes".to_string();
+
+        assert_eq!(
+            file.render(edited.clone()).0,
+            format!("# Work\n(A)   Call  mom\n\n{}\n", edited[1])
+        );
+    }
+
+    #[test]
+    fn refresh() {
+        let dir = std::env::temp_dir().join(format!("effitask-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let todo = dir.join("todo.txt").to_string_lossy().to_string();
+        let done = dir.join("done.txt").to_string_lossy().to_string();
+        std::fs::write(&todo, "Call mom\nPay bills\n").unwrap();
+        std::fs::write(&done, "x 2024-01-01 Buy milk\n").unwrap();
+
+        let mut list = List::new();
+        list.load(None, &todo, &done);
+        assert!(!list.refresh());
+
+        std::fs::write(&todo, "Call mom\n").unwrap();
+        assert!(list.refresh());
+
+        let subjects: Vec<_> = list.iter().map(|x| x.subject.as_str()).collect();
+        assert_eq!(subjects, ["Call mom", "Buy milk"]);
+        assert_eq!(list.tasks[1].id, 1);
+        assert!(list.tasks[1].archived);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn auto_archive() {
+        let dir = std::env::temp_dir().join(format!("effitask-archive-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let todo = dir.join("todo.txt").to_string_lossy().to_string();
+        let done = dir.join("done.txt").to_string_lossy().to_string();
+        std::fs::write(&todo, "Call mom\nPay bills\n").unwrap();
+        std::fs::write(&done, "").unwrap();
+
+        let mut list = List::from_files(&todo, &done);
+        list.set_auto_archive(false);
+        list.tasks[0].complete();
+        list.write().unwrap();
+        list.reload();
+
+        assert!(list.tasks[0].finished);
+        assert!(!list.tasks[0].archived);
+
+        list.set_auto_archive(true);
+        list.write().unwrap();
+        list.reload();
+
+        assert_eq!(std::fs::read_to_string(&todo).unwrap(), "Pay bills\n");
+        assert!(list.tasks[1].archived);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symlink() {
+        let dir = std::env::temp_dir().join(format!("effitask-symlink-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let synced = dir.join("synced.txt");
+        let todo = dir.join("todo.txt");
+        let done = dir.join("done.txt").to_string_lossy().to_string();
+        std::fs::write(&synced, "Call mom\n").unwrap();
+        std::fs::write(&done, "").unwrap();
+        std::os::unix::fs::symlink(&synced, &todo).unwrap();
+
+        let mut list = List::from_files(&todo.to_string_lossy(), &done);
+        list.tasks[0].subject = "Call dad".to_string();
+        list.write().unwrap();
+
+        assert!(std::fs::symlink_metadata(&todo).unwrap().is_symlink());
+        assert_eq!(std::fs::read_to_string(&synced).unwrap(), "Call dad\n");
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn unchanged() {
+        let dir = std::env::temp_dir().join(format!("effitask-unchanged-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = |x: &str| dir.join(x).to_string_lossy().to_string();
+        std::fs::write(path("work.txt"), "Call mom\n").unwrap();
+        std::fs::write(path("personal.txt"), "Pay bills\n").unwrap();
+        std::fs::write(path("work-done.txt"), "").unwrap();
+        std::fs::write(path("personal-done.txt"), "").unwrap();
+
+        let mut list = List::new();
+        list.load(Some("work"), &path("work.txt"), &path("work-done.txt"));
+        list.load(
+            Some("personal"),
+            &path("personal.txt"),
+            &path("personal-done.txt"),
+        );
+        list.tasks[0].subject = "Call dad".to_string();
+        list.write().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(path("work.txt")).unwrap(),
+            "Call dad\n"
+        );
+        assert!(std::path::Path::new(&path("work.txt.bak")).exists());
+        assert!(!std::path::Path::new(&path("personal.txt.bak")).exists());
+        assert!(!std::path::Path::new(&path("work-done.txt.bak")).exists());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn conflict() {
+        let dir = std::env::temp_dir().join(format!("effitask-conflict-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = |x: &str| dir.join(x).to_string_lossy().to_string();
+        std::fs::write(path("work.txt"), "Call mom\n").unwrap();
+        std::fs::write(path("work-done.txt"), "").unwrap();
+        std::fs::write(path("personal.txt"), "Pay bills\n").unwrap();
+        std::fs::write(path("personal-done.txt"), "").unwrap();
+
+        let mut list = List::new();
+        list.load(Some("work"), &path("work.txt"), &path("work-done.txt"));
+        list.load(
+            Some("personal"),
+            &path("personal.txt"),
+            &path("personal-done.txt"),
+        );
+        std::fs::write(path("personal.txt"), "Pay taxes\n").unwrap();
+
+        list.tasks[0].subject = "Call dad".to_string();
+        list.write().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path("personal.txt")).unwrap(),
+            "Pay taxes\n"
+        );
+
+        list.tasks[1].subject = "Pay rent".to_string();
+        assert!(list.write().is_err());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -1,7 +1,13 @@
+pub mod bulk;
+pub mod history;
+pub mod index;
 mod list;
 pub mod markup;
+pub mod quick_add;
 pub mod task;
 
+pub use history::History;
+pub use index::Index;
 pub use list::List;
 pub use markup::Markup;
 pub use task::Task;
@@ -0,0 +1,287 @@
+use chrono::Datelike as _;
+
+const WEEKDAYS: [(&str, chrono::Weekday); 7] = [
+    ("monday", chrono::Weekday::Mon),
+    ("tuesday", chrono::Weekday::Tue),
+    ("wednesday", chrono::Weekday::Wed),
+    ("thursday", chrono::Weekday::Thu),
+    ("friday", chrono::Weekday::Fri),
+    ("saturday", chrono::Weekday::Sat),
+    ("sunday", chrono::Weekday::Sun),
+];
+
+pub fn parse(text: &str, today: chrono::NaiveDate) -> String {
+    let words = text.split_whitespace().collect::<Vec<_>>();
+
+    let has_priority = words.first().is_some_and(|x| is_priority(x));
+    let has_due = has_tag(&words, "due");
+    let has_threshold = has_tag(&words, "t");
+    let has_recurrence = has_tag(&words, "rec");
+
+    let mut subject = Vec::new();
+    let mut priority = None;
+    let mut due = None;
+    let mut threshold = None;
+    let mut recurrence = None;
+    let mut i = 0;
+
+    while i < words.len() {
+        let rest = &words[i..];
+        let word = rest[0].to_lowercase();
+
+        if priority.is_none() && !has_priority {
+            if let Some(p) = self::priority(rest[0]) {
+                priority = Some(p);
+                i += 1;
+                continue;
+            }
+        }
+
+        if threshold.is_none() && !has_threshold && word == "defer" {
+            let skip = if is(rest, 1, "until") { 2 } else { 1 };
+
+            if let Some((date, n)) = date(rest.get(skip..).unwrap_or_default(), today) {
+                threshold = Some(date);
+                i += skip + n;
+                continue;
+            }
+        }
+
+        if recurrence.is_none() && !has_recurrence && word == "every" {
+            if let Some((every, n)) = every(&rest[1..]) {
+                recurrence = Some(every);
+                i += 1 + n;
+                continue;
+            }
+        }
+
+        if due.is_none() && !has_due {
+            let keyword = word == "due" || word == "by";
+            let skip = usize::from(keyword);
+
+            if let Some((date, n)) = date(rest.get(skip..).unwrap_or_default(), today) {
+                if keyword || is_end(&rest[skip + n..]) {
+                    due = Some(date);
+                    i += skip + n;
+                    continue;
+                }
+            }
+        }
+
+        subject.push(rest[0]);
+        i += 1;
+    }
+
+    let mut line = subject.join(" ");
+
+    if let Some(priority) = priority {
+        line = format!("({priority}) {line}");
+    }
+
+    let tags = [
+        ("due", due.map(|x| x.format("%Y-%m-%d").to_string())),
+        ("t", threshold.map(|x| x.format("%Y-%m-%d").to_string())),
+        ("rec", recurrence),
+    ];
+
+    for (key, value) in tags {
+        if let Some(value) = value {
+            line.push_str(&format!(" {key}:{value}"));
+        }
+    }
+
+    line.trim().to_string()
+}
+
+fn has_tag(words: &[&str], key: &str) -> bool {
+    words.iter().any(|x| {
+        x.split_once(':')
+            .is_some_and(|(k, v)| k.eq_ignore_ascii_case(key) && !v.is_empty())
+    })
+}
+
+fn is_priority(word: &str) -> bool {
+    let bytes = word.as_bytes();
+
+    bytes.len() == 3 && bytes[0] == b'(' && bytes[1].is_ascii_uppercase() && bytes[2] == b')'
+}
+
+fn is_end(words: &[&str]) -> bool {
+    words
+        .iter()
+        .all(|x| x.starts_with(['+', '@', '#']) || x.contains(':') || priority(x).is_some())
+}
+
+fn is(words: &[&str], n: usize, expected: &str) -> bool {
+    words
+        .get(n)
+        .is_some_and(|x| x.eq_ignore_ascii_case(expected))
+}
+
+fn priority(word: &str) -> Option<char> {
+    let mut chars = word.strip_prefix('!')?.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => Some(c.to_ascii_uppercase()),
+        _ => None,
+    }
+}
+
+fn date(words: &[&str], today: chrono::NaiveDate) -> Option<(chrono::NaiveDate, usize)> {
+    let word = |n: usize| words.get(n).map(|x| x.to_lowercase());
+
+    match word(0)?.as_str() {
+        "today" => Some((today, 1)),
+        "tomorrow" => today.succ_opt().map(|x| (x, 1)),
+        "next" => match word(1)?.as_str() {
+            "week" => add(today, 1, 'w').map(|x| (x, 2)),
+            "month" => add(today, 1, 'm').map(|x| (x, 2)),
+            "year" => add(today, 1, 'y').map(|x| (x, 2)),
+            day => next_weekday(today, day).map(|x| (x, 2)),
+        },
+        "in" => {
+            let n = word(1)?.parse().ok()?;
+            let unit = unit(&word(2)?)?;
+
+            add(today, n, unit).map(|x| (x, 3))
+        }
+        day => weekday(today, day).map(|x| (x, 1)),
+    }
+}
+
+fn every(words: &[&str]) -> Option<(String, usize)> {
+    let first = words.first()?.to_lowercase();
+
+    if let Some(unit) = unit(&first) {
+        return Some((format!("1{unit}"), 1));
+    }
+
+    let n = first.parse::<u32>().ok().filter(|x| *x > 0)?;
+    let unit = unit(&words.get(1)?.to_lowercase())?;
+
+    Some((format!("{n}{unit}"), 2))
+}
+
+fn unit(word: &str) -> Option<char> {
+    match word {
+        "day" | "days" => Some('d'),
+        "week" | "weeks" => Some('w'),
+        "month" | "months" => Some('m'),
+        "year" | "years" => Some('y'),
+        _ => None,
+    }
+}
+
+fn add(date: chrono::NaiveDate, n: u32, unit: char) -> Option<chrono::NaiveDate> {
+    match unit {
+        'd' => date.checked_add_days(chrono::Days::new(n.into())),
+        'w' => date.checked_add_days(chrono::Days::new(u64::from(n) * 7)),
+        'm' => date.checked_add_months(chrono::Months::new(n)),
+        'y' => date.checked_add_months(chrono::Months::new(n * 12)),
+        _ => None,
+    }
+}
+
+fn weekday(today: chrono::NaiveDate, name: &str) -> Option<chrono::NaiveDate> {
+    let (_, weekday) = WEEKDAYS.iter().find(|(x, _)| *x == name)?;
+
+    let days = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+    let days = if days == 0 { 7 } else { days };
+
+    add(today, days, 'd')
+}
+
+fn next_weekday(today: chrono::NaiveDate, name: &str) -> Option<chrono::NaiveDate> {
+    let (_, weekday) = WEEKDAYS.iter().find(|(x, _)| *x == name)?;
+
+    let days = 7 - today.weekday().num_days_from_monday() + weekday.num_days_from_monday();
+
+    add(today, days, 'd')
+}
+
+#[cfg(test)]
+mod tests {
+    fn parse(text: &str) -> String {
+        super::parse(text, "2024-01-10".parse().unwrap())
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(parse("Call mom tomorrow"), "Call mom due:2024-01-11");
+        assert_eq!(
+            parse("Pay bills by next friday"),
+            "Pay bills due:2024-01-19"
+        );
+        assert_eq!(parse("Call dad friday"), "Call dad due:2024-01-12");
+        assert_eq!(parse("Call dad next monday"), "Call dad due:2024-01-15");
+        assert_eq!(
+            parse("Water plants wednesday"),
+            "Water plants due:2024-01-17"
+        );
+        assert_eq!(
+            parse("Renew passport in 3 months"),
+            "Renew passport due:2024-04-10"
+        );
+        assert_eq!(
+            parse("Book flight due next week"),
+            "Book flight due:2024-01-17"
+        );
+    }
+
+    #[test]
+    fn keywords() {
+        assert_eq!(
+            parse("!a Water plants every 2 weeks defer until monday +home"),
+            "(A) Water plants +home t:2024-01-15 rec:2w"
+        );
+        assert_eq!(
+            parse("Backup every week today"),
+            "Backup due:2024-01-10 rec:1w"
+        );
+        assert_eq!(parse("Defer the meeting"), "Defer the meeting");
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(parse("Put milk in fridge"), "Put milk in fridge");
+        assert_eq!(parse("Buy milk !important"), "Buy milk !important");
+        assert_eq!(
+            parse("(B) Fix bike due:2024-02-01"),
+            "(B) Fix bike due:2024-02-01"
+        );
+        assert_eq!(parse(""), "");
+    }
+
+    #[test]
+    fn end() {
+        assert_eq!(
+            parse("Prepare slides for friday meeting"),
+            "Prepare slides for friday meeting"
+        );
+        assert_eq!(
+            parse("Prepare slides for the meeting friday +work"),
+            "Prepare slides for the meeting +work due:2024-01-12"
+        );
+        assert_eq!(
+            parse("Call today's winner tomorrow"),
+            "Call today's winner due:2024-01-11"
+        );
+        assert_eq!(
+            parse("Send report by friday to the team"),
+            "Send report to the team due:2024-01-12"
+        );
+    }
+
+    #[test]
+    fn existing() {
+        assert_eq!(
+            parse("Call mom tomorrow due:2024-02-01"),
+            "Call mom tomorrow due:2024-02-01"
+        );
+        assert_eq!(parse("(B) Fix bike !A"), "(B) Fix bike !A");
+        assert_eq!(
+            parse("Water plants defer until monday every week t:2024-01-01 rec:2w"),
+            "Water plants defer until monday every week t:2024-01-01 rec:2w"
+        );
+    }
+}
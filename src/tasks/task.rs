@@ -1,7 +1,10 @@
-#[derive(Clone, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Debug, Default)]
 pub struct Task {
     inner: todo_txt::task::Extended,
     pub id: usize,
+    pub archived: bool,
+    pub workspace: Option<String>,
+    fingerprint: u64,
 }
 
 impl Task {
@@ -9,28 +12,149 @@ impl Task {
         Self {
             inner: todo_txt::task::Extended::default(),
             id: 0,
+            archived: false,
+            workspace: None,
+            fingerprint: 0,
         }
     }
 
-    pub fn markup_subject(&self) -> String {
-        let mut subject = Self::markup_escape(&self.subject);
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    pub fn is(&self, other: &Self) -> bool {
+        match (self.tags.get("id"), other.tags.get("id")) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.fingerprint == other.fingerprint,
+        }
+    }
+
+    pub fn inherit(&mut self, previous: &Self) {
+        self.id = previous.id;
+        self.fingerprint = previous.fingerprint;
+    }
+
+    pub fn notes_dir() -> std::path::PathBuf {
+        match std::env::var("TODO_NOTES_DIR") {
+            Ok(dir) => dir.into(),
+            Err(_) => std::path::PathBuf::from(std::env::var("TODO_DIR").unwrap_or_default())
+                .join("notes"),
+        }
+    }
+
+    pub fn note_path(&self, notes_dir: &std::path::Path) -> Option<std::path::PathBuf> {
+        match self.note {
+            todo_txt::task::Note::Long { ref filename, .. } => Some(notes_dir.join(filename)),
+            _ => None,
+        }
+    }
+
+    pub fn name_note(&mut self) {
+        if let todo_txt::task::Note::Short(ref content) = self.note {
+            self.note = todo_txt::task::Note::Long {
+                filename: Self::note_filename(),
+                content: content.clone(),
+            };
+        }
+    }
+
+    pub fn write_note(&mut self, notes_dir: &std::path::Path) -> std::io::Result<()> {
+        self.name_note();
+
+        if let Some(path) = self.note_path(notes_dir) {
+            std::fs::create_dir_all(notes_dir)?;
+            std::fs::write(path, self.note.content().unwrap_or_default())?;
+        }
+
+        Ok(())
+    }
+
+    fn note_filename() -> String {
+        use rand::Rng as _;
+
+        let ext = std::env::var("TODO_NOTE_EXT").unwrap_or_else(|_| ".txt".to_string());
+        let name: String = rand::thread_rng()
+            .sample_iter(&rand::distributions::Alphanumeric)
+            .map(char::from)
+            .take(3)
+            .collect();
+
+        format!("{name}{ext}")
+    }
+
+    fn hash(line: &str) -> u64 {
+        use std::hash::{Hash as _, Hasher as _};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        line.trim().hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    pub fn markup_subject(&self, highlights: &[(usize, usize)]) -> String {
+        let subject = self.subject.as_str();
+
+        let regex = regex::Regex::new(r"[\w]+://[^\s]+").unwrap();
+        let links = regex
+            .find_iter(subject)
+            .map(|x| (x.start(), x.end()))
+            .collect::<Vec<_>>();
+
+        let regex = regex::Regex::new(r"(?:^|[\s])([\+@#][\w\-\\]+)").unwrap();
+        let tags = regex
+            .captures_iter(subject)
+            .filter_map(|x| x.get(1))
+            .map(|x| (x.start(), x.end()))
+            .collect::<Vec<_>>();
+
+        let mut boundaries = vec![0, subject.len()];
+        for &(start, end) in links.iter().chain(&tags).chain(highlights) {
+            boundaries.extend([start.min(subject.len()), end.min(subject.len())]);
+        }
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        let inside = |ranges: &[(usize, usize)], position: usize| {
+            ranges
+                .iter()
+                .copied()
+                .find(|&(start, end)| start <= position && position < end)
+        };
+
+        let mut markup = String::new();
+        let mut link = None;
+
+        for window in boundaries.windows(2) {
+            let (start, end) = (window[0], window[1]);
+
+            let current = inside(&links, start);
+            if current != link {
+                if link.is_some() {
+                    markup.push_str("</a>");
+                }
+                if let Some((start, end)) = current {
+                    let url = Self::markup_escape(&subject[start..end]);
+                    markup.push_str(&format!("<a href=\"{url}\">"));
+                }
+                link = current;
+            }
 
-        let regex = regex::Regex::new(r"(?P<url>[\w]+://[^\s]+)").unwrap();
-        subject = regex
-            .replace_all(&subject, |caps: &regex::Captures<'_>| {
-                format!(
-                    "<a href=\"{url}\">{url}</a>",
-                    url = caps[1].replace('&', "&amp;")
-                )
-            })
-            .into_owned();
+            let mut text = Self::markup_escape(&subject[start..end]);
+            if inside(highlights, start).is_some() {
+                text = format!("<u>{text}</u>");
+            }
+            if inside(&tags, start).is_some() {
+                text = format!("<b>{text}</b>");
+            }
 
-        let regex = regex::Regex::new(r"(?P<space>^|[\s])(?P<tag>[\+@#][\w\-\\]+)").unwrap();
-        subject = regex
-            .replace_all(&subject, "$space<b>$tag</b>")
-            .into_owned();
+            markup.push_str(&text);
+        }
+
+        if link.is_some() {
+            markup.push_str("</a>");
+        }
 
-        subject
+        markup
     }
 
     fn markup_escape(text: &str) -> String {
@@ -40,6 +164,32 @@ impl Task {
 
 impl todo_txt::Task for Task {}
 
+impl Task {
+    fn value(&self) -> impl Ord + '_ {
+        (&self.inner, self.id, self.archived, &self.workspace)
+    }
+}
+
+impl PartialEq for Task {
+    fn eq(&self, other: &Self) -> bool {
+        self.value() == other.value()
+    }
+}
+
+impl Eq for Task {}
+
+impl PartialOrd for Task {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Task {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.value().cmp(&other.value())
+    }
+}
+
 impl AsRef<todo_txt::task::Simple> for Task {
     fn as_ref(&self) -> &todo_txt::task::Simple {
         &self.inner
@@ -52,15 +202,28 @@ impl std::str::FromStr for Task {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let inner = todo_txt::task::Extended::from_str(s)?;
 
-        Ok(Self { inner, id: 0 })
+        Ok(Self {
+            inner,
+            id: 0,
+            archived: false,
+            workspace: None,
+            fingerprint: Self::hash(s),
+        })
     }
 }
 
 impl From<String> for Task {
     fn from(value: String) -> Self {
+        let fingerprint = Self::hash(&value);
         let inner = todo_txt::task::Extended::from(value);
 
-        Self { inner, id: 0 }
+        Self {
+            inner,
+            id: 0,
+            archived: false,
+            workspace: None,
+            fingerprint,
+        }
     }
 }
 
@@ -98,8 +261,44 @@ mod tests {
         task.subject = "P&T keep focus on long term +HoWE".to_string();
 
         assert_eq!(
-            task.markup_subject(),
+            task.markup_subject(&[]),
             "P&amp;T keep focus on long term <b>+HoWE</b>"
         );
     }
+
+    #[test]
+    fn markup_highlights() {
+        let mut task = Task::new();
+        task.subject = "Call mom +family http://example.org/?a&b".to_string();
+
+        assert_eq!(
+            task.markup_subject(&[(5, 8), (10, 13)]),
+            "Call <u>mom</u> <b>+</b><b><u>fam</u></b><b>ily</b> <a href=\"http://example.org/?a&amp;b\">http://example.org/?a&amp;b</a>"
+        );
+    }
+
+    #[test]
+    fn identity() {
+        let task = Task::from("(A) Call mom".to_string());
+        let mut edited = task.clone();
+        edited.subject = "Call dad".to_string();
+
+        assert!(edited.is(&task));
+        assert!(!Task::from("Call dad".to_string()).is(&task));
+
+        let task = Task::from("Call mom id:42".to_string());
+
+        assert!(Task::from("x Call mom id:42".to_string()).is(&task));
+    }
+
+    #[test]
+    fn equality() {
+        let task = Task::from("Call mom".to_string());
+        let mut parsed = task.clone();
+        parsed.inherit(&Task::new());
+
+        assert_ne!(parsed.fingerprint(), task.fingerprint());
+        assert_eq!(parsed, task);
+        assert_eq!(parsed.cmp(&task), std::cmp::Ordering::Equal);
+    }
 }
@@ -0,0 +1,278 @@
+use crate::tasks::bulk::Action;
+use gtk::prelude::*;
+
+#[derive(Debug)]
+pub enum MsgInput {
+    Apply(Action),
+    Defer(Option<i64>),
+    Due(Option<i64>),
+    Tag(bool),
+}
+
+#[derive(Debug)]
+pub enum MsgOutput {
+    Apply(Action),
+}
+
+pub struct Model;
+
+impl Model {
+    fn date(days: Option<i64>) -> Option<chrono::NaiveDate> {
+        days.and_then(|x| crate::date::today().checked_add_signed(chrono::Duration::days(x)))
+    }
+
+    fn tag(widgets: &ModelWidgets, add: bool) -> Option<Action> {
+        let text = widgets.tag.text();
+        let text = text.trim();
+
+        if text.trim_start_matches(['+', '@']).is_empty() || text.contains(char::is_whitespace) {
+            log::warn!("Invalid project or context '{text}'");
+            return None;
+        }
+
+        let action = if let Some(context) = text.strip_prefix('@') {
+            let context = context.to_string();
+
+            if add {
+                Action::AddContext(context)
+            } else {
+                Action::RemoveContext(context)
+            }
+        } else {
+            let project = text.trim_start_matches('+').to_string();
+
+            if add {
+                Action::AddProject(project)
+            } else {
+                Action::RemoveProject(project)
+            }
+        };
+
+        widgets.tag.set_text("");
+
+        Some(action)
+    }
+}
+
+#[relm4::component(pub)]
+impl relm4::Component for Model {
+    type CommandOutput = ();
+    type Init = ();
+    type Input = MsgInput;
+    type Output = MsgOutput;
+
+    fn init(
+        _: Self::Init,
+        root: Self::Root,
+        sender: relm4::ComponentSender<Self>,
+    ) -> relm4::ComponentParts<Self> {
+        let model = Self;
+
+        let widgets = view_output!();
+
+        relm4::ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        msg: Self::Input,
+        sender: relm4::ComponentSender<Self>,
+        _: &Self::Root,
+    ) {
+        use MsgInput::*;
+
+        let action = match msg {
+            Apply(action) => Some(action),
+            Defer(days) => Some(Action::Defer(Self::date(days))),
+            Due(days) => Some(Action::Due(Self::date(days))),
+            Tag(add) => Self::tag(widgets, add),
+        };
+
+        for popover in [
+            &widgets.defer_popover,
+            &widgets.due_popover,
+            &widgets.flag_popover,
+            &widgets.priority_popover,
+            &widgets.tag_popover,
+        ] {
+            popover.popdown();
+        }
+
+        if let Some(action) = action {
+            sender.output(MsgOutput::Apply(action)).ok();
+        }
+    }
+
+    view! {
+        gtk::ActionBar {
+            pack_start = &gtk::Button {
+                set_icon_name: "object-select",
+                set_tooltip_text: Some("Mark as done"),
+
+                connect_clicked => MsgInput::Apply(Action::Complete),
+            },
+            pack_start = &gtk::MenuButton {
+                set_label: "Priority",
+
+                #[wrap(Some)]
+                #[name = "priority_popover"]
+                set_popover = &gtk::Popover {
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Horizontal,
+
+                        gtk::Button {
+                            set_label: "A",
+                            connect_clicked => MsgInput::Apply(Action::Priority(0)),
+                        },
+                        gtk::Button {
+                            set_label: "B",
+                            connect_clicked => MsgInput::Apply(Action::Priority(1)),
+                        },
+                        gtk::Button {
+                            set_label: "C",
+                            connect_clicked => MsgInput::Apply(Action::Priority(2)),
+                        },
+                        gtk::Button {
+                            set_label: "D",
+                            connect_clicked => MsgInput::Apply(Action::Priority(3)),
+                        },
+                        gtk::Button {
+                            set_label: "E",
+                            connect_clicked => MsgInput::Apply(Action::Priority(4)),
+                        },
+                        gtk::Button {
+                            set_label: "None",
+                            connect_clicked => MsgInput::Apply(Action::Priority(26)),
+                        },
+                    },
+                },
+            },
+            pack_start = &gtk::MenuButton {
+                set_label: "Tags",
+
+                #[wrap(Some)]
+                #[name = "tag_popover"]
+                set_popover = &gtk::Popover {
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 5,
+
+                        #[name = "tag"]
+                        gtk::Entry {
+                            set_placeholder_text: Some("+project or @context"),
+                        },
+                        gtk::Box {
+                            set_homogeneous: true,
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_spacing: 5,
+
+                            gtk::Button {
+                                set_label: "Add",
+                                connect_clicked => MsgInput::Tag(true),
+                            },
+                            gtk::Button {
+                                set_label: "Remove",
+                                connect_clicked => MsgInput::Tag(false),
+                            },
+                        },
+                    },
+                },
+            },
+            pack_start = &gtk::MenuButton {
+                set_label: "Due",
+
+                #[wrap(Some)]
+                #[name = "due_popover"]
+                set_popover = &gtk::Popover {
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+
+                        gtk::Button {
+                            add_css_class: "flat",
+                            set_label: "Today",
+                            connect_clicked => MsgInput::Due(Some(0)),
+                        },
+                        gtk::Button {
+                            add_css_class: "flat",
+                            set_label: "Tomorrow",
+                            connect_clicked => MsgInput::Due(Some(1)),
+                        },
+                        gtk::Button {
+                            add_css_class: "flat",
+                            set_label: "One day later",
+                            connect_clicked => MsgInput::Apply(Action::ShiftDue(1)),
+                        },
+                        gtk::Button {
+                            add_css_class: "flat",
+                            set_label: "One week later",
+                            connect_clicked => MsgInput::Apply(Action::ShiftDue(7)),
+                        },
+                        gtk::Button {
+                            add_css_class: "flat",
+                            set_label: "Remove due date",
+                            connect_clicked => MsgInput::Due(None),
+                        },
+                    },
+                },
+            },
+            pack_start = &gtk::MenuButton {
+                set_label: "Defer",
+
+                #[wrap(Some)]
+                #[name = "defer_popover"]
+                set_popover = &gtk::Popover {
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+
+                        gtk::Button {
+                            add_css_class: "flat",
+                            set_label: "Until tomorrow",
+                            connect_clicked => MsgInput::Defer(Some(1)),
+                        },
+                        gtk::Button {
+                            add_css_class: "flat",
+                            set_label: "Until next week",
+                            connect_clicked => MsgInput::Defer(Some(7)),
+                        },
+                        gtk::Button {
+                            add_css_class: "flat",
+                            set_label: "Remove threshold date",
+                            connect_clicked => MsgInput::Defer(None),
+                        },
+                    },
+                },
+            },
+            pack_start = &gtk::MenuButton {
+                set_icon_name: "emblem-favorite",
+                set_tooltip_text: Some("Flag"),
+
+                #[wrap(Some)]
+                #[name = "flag_popover"]
+                set_popover = &gtk::Popover {
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+
+                        gtk::Button {
+                            add_css_class: "flat",
+                            set_label: "Flag",
+                            connect_clicked => MsgInput::Apply(Action::Flag(true)),
+                        },
+                        gtk::Button {
+                            add_css_class: "flat",
+                            set_label: "Unflag",
+                            connect_clicked => MsgInput::Apply(Action::Flag(false)),
+                        },
+                    },
+                },
+            },
+            pack_end = &gtk::Button {
+                add_css_class: "destructive-action",
+                set_icon_name: "edit-delete",
+                set_tooltip_text: Some("Delete"),
+
+                connect_clicked => MsgInput::Apply(Action::Delete),
+            },
+        }
+    }
+}
@@ -25,6 +25,7 @@ impl From<Column> for i32 {
 
 #[derive(Debug)]
 pub enum MsgInput {
+    Drop(Box<crate::tasks::Task>, f64, f64),
     SelectionChange,
     UpdateFilters(Vec<(String, (u32, u32))>),
     UpdateTasks(Vec<crate::tasks::Task>),
@@ -32,9 +33,14 @@ pub enum MsgInput {
 
 #[derive(Debug)]
 pub enum MsgOutput {
+    Bulk(crate::tasks::bulk::Action, Vec<crate::tasks::Task>),
     Complete(Box<crate::tasks::Task>),
+    Delete(Box<crate::tasks::Task>),
+    Duplicate(Box<crate::tasks::Task>),
     Edit(Box<crate::tasks::Task>),
     Filters(Vec<String>),
+    Move(Box<crate::tasks::Task>, String),
+    Reorder(Vec<(crate::tasks::Task, crate::tasks::Task)>),
 }
 
 pub struct Model {
@@ -131,22 +137,25 @@ impl Model {
 #[relm4::component(pub)]
 impl relm4::Component for Model {
     type CommandOutput = ();
-    type Init = ();
+    type Init = String;
     type Input = MsgInput;
     type Output = MsgOutput;
 
     fn init(
-        _: Self::Init,
+        init: Self::Init,
         root: Self::Root,
         sender: relm4::ComponentSender<Self>,
     ) -> relm4::ComponentParts<Self> {
-        let tasks = crate::widgets::tasks::Model::builder().launch(()).forward(
-            sender.output_sender(),
-            |output| match output {
+        let tasks = crate::widgets::tasks::Model::builder()
+            .launch(init)
+            .forward(sender.output_sender(), |output| match output {
+                super::task::MsgOutput::Bulk(action, tasks) => MsgOutput::Bulk(action, tasks),
                 super::task::MsgOutput::Complete(task) => MsgOutput::Complete(task),
+                super::task::MsgOutput::Delete(task) => MsgOutput::Delete(task),
+                super::task::MsgOutput::Duplicate(task) => MsgOutput::Duplicate(task),
                 super::task::MsgOutput::Edit(task) => MsgOutput::Edit(task),
-            },
-        );
+                super::task::MsgOutput::Reorder(changes) => MsgOutput::Reorder(changes),
+            });
 
         let columns = vec![
             gtk::glib::types::Type::STRING,
@@ -162,6 +171,23 @@ impl relm4::Component for Model {
 
         let widgets = view_output!();
 
+        let drop = gtk::DropTarget::new(
+            gtk::glib::BoxedAnyObject::static_type(),
+            gtk::gdk::DragAction::MOVE,
+        );
+        let drop_sender = sender.clone();
+        drop.connect_drop(move |_, value, x, y| {
+            let Ok(object) = value.get::<gtk::glib::BoxedAnyObject>() else {
+                return false;
+            };
+
+            let task = object.borrow::<crate::tasks::Task>().clone();
+            drop_sender.input(MsgInput::Drop(Box::new(task), x, y));
+
+            true
+        });
+        widgets.tree_view.add_controller(drop);
+
         let selection = widgets.tree_view.selection();
         selection.set_mode(gtk::SelectionMode::Multiple);
         selection.connect_changed(move |_| {
@@ -192,6 +218,17 @@ impl relm4::Component for Model {
         use MsgInput::*;
 
         match msg {
+            Drop(task, x, y) => {
+                let filter = widgets
+                    .tree_view
+                    .dest_row_at_pos(x as i32, y as i32)
+                    .and_then(|(path, _)| path)
+                    .and_then(|path| self.filters.get(&path).cloned());
+
+                if let Some(filter) = filter {
+                    sender.output(MsgOutput::Move(task, filter)).ok();
+                }
+            }
             SelectionChange => {
                 let mut filters = Vec::new();
 
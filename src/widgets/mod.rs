@@ -1,3 +1,4 @@
+pub mod bulk;
 pub mod calendar;
 pub mod circle;
 pub mod filter;
@@ -1,123 +1,66 @@
 use gtk::prelude::*;
 use relm4::ComponentController as _;
 
-#[derive(Clone, Copy)]
-pub enum Type {
-    Projects,
-    Contexts,
-    Hashtags,
-}
-
 #[derive(Debug)]
 pub enum MsgInput {
+    Bulk(crate::tasks::bulk::Action, Vec<crate::tasks::Task>),
     Complete(Box<crate::tasks::Task>),
+    Delete(Box<crate::tasks::Task>),
+    Duplicate(Box<crate::tasks::Task>),
     Edit(Box<crate::tasks::Task>),
+    Move(Box<crate::tasks::Task>, String),
+    Reorder(Vec<(crate::tasks::Task, crate::tasks::Task)>),
     UpdateFilters(Vec<String>),
     Update,
 }
 
 #[derive(Debug)]
 pub enum MsgOutput {
+    Bulk(crate::tasks::bulk::Action, Vec<crate::tasks::Task>),
     Complete(Box<crate::tasks::Task>),
+    Delete(Box<crate::tasks::Task>),
+    Duplicate(Box<crate::tasks::Task>),
     Edit(Box<crate::tasks::Task>),
+    Move(
+        Box<crate::tasks::Task>,
+        crate::query::Tag,
+        Vec<String>,
+        String,
+    ),
+    Reorder(Vec<(crate::tasks::Task, crate::tasks::Task)>),
 }
 
 pub struct Model {
-    tag: Type,
+    tag: crate::query::Tag,
     filter: relm4::Controller<super::filter::Model>,
+    filters: Vec<String>,
 }
 
 impl Model {
     fn update_tags(&self) {
         let list = crate::application::tasks();
-        let tags = match self.tag {
-            Type::Projects => list.projects(),
-            Type::Contexts => list.contexts(),
-            Type::Hashtags => list.hashtags(),
-        };
-
-        let tags = tags
-            .iter()
-            .map(|x| (x.clone(), self.progress(&list, x)))
-            .filter(|&(_, (done, total))| done != total)
-            .collect();
+        let tags = crate::query::tags(&list, self.tag);
 
         self.filter
             .emit(crate::widgets::filter::MsgInput::UpdateFilters(tags));
     }
 
-    fn progress(&self, list: &crate::tasks::List, current: &str) -> (u32, u32) {
-        list.tasks
-            .iter()
-            .filter(|x| {
-                for tag in self.tags(x) {
-                    if tag == current || tag.starts_with(&format!("{current}-")) {
-                        return true;
-                    }
-                }
-
-                false
-            })
-            .fold((0, 0), |(mut done, total), x| {
-                if x.finished {
-                    done += 1;
-                }
-
-                (done, total + 1)
-            })
-    }
+    fn update_tasks(&mut self, filters: &[String]) {
+        self.filters = filters.to_vec();
 
-    fn update_tasks(&self, filters: &[String]) {
-        let today = crate::date::today();
         let preferences = crate::application::preferences();
         let list = crate::application::tasks();
-
-        let tasks = list
-            .tasks
-            .iter()
-            .filter(|x| {
-                let tags = self.tags(x);
-
-                (preferences.done || !x.finished)
-                    && !tags.is_empty()
-                    && Self::has_filter(tags, filters)
-                    && (preferences.defered
-                        || x.threshold_date.is_none()
-                        || x.threshold_date.unwrap() <= today)
-            })
-            .cloned()
-            .collect();
+        let tasks =
+            crate::query::tagged(&list, &preferences, self.tag, filters, crate::date::today());
 
         self.filter
             .emit(crate::widgets::filter::MsgInput::UpdateTasks(tasks));
     }
-
-    fn tags<'a>(&self, task: &'a crate::tasks::Task) -> &'a [String] {
-        match self.tag {
-            Type::Projects => &task.projects,
-            Type::Contexts => &task.contexts,
-            Type::Hashtags => &task.hashtags,
-        }
-    }
-
-    fn has_filter(tags: &[String], filters: &[String]) -> bool {
-        if filters.is_empty() {
-            return true;
-        }
-
-        for filter in filters {
-            if tags.contains(filter) {
-                return true;
-            }
-        }
-
-        false
-    }
 }
 
 #[relm4::component(pub)]
 impl relm4::SimpleComponent for Model {
-    type Init = Type;
+    type Init = crate::query::Tag;
     type Input = MsgInput;
     type Output = MsgOutput;
 
@@ -128,16 +71,24 @@ impl relm4::SimpleComponent for Model {
     ) -> relm4::ComponentParts<Self> {
         use relm4::Component as _;
 
-        let filter =
-            super::filter::Model::builder()
-                .launch(())
-                .forward(sender.input_sender(), |output| match output {
-                    super::filter::MsgOutput::Complete(task) => MsgInput::Complete(task),
-                    super::filter::MsgOutput::Edit(task) => MsgInput::Edit(task),
-                    super::filter::MsgOutput::Filters(filters) => MsgInput::UpdateFilters(filters),
-                });
-
-        let model = Self { tag: init, filter };
+        let filter = super::filter::Model::builder()
+            .launch(init.name().to_string())
+            .forward(sender.input_sender(), |output| match output {
+                super::filter::MsgOutput::Bulk(action, tasks) => MsgInput::Bulk(action, tasks),
+                super::filter::MsgOutput::Complete(task) => MsgInput::Complete(task),
+                super::filter::MsgOutput::Delete(task) => MsgInput::Delete(task),
+                super::filter::MsgOutput::Duplicate(task) => MsgInput::Duplicate(task),
+                super::filter::MsgOutput::Edit(task) => MsgInput::Edit(task),
+                super::filter::MsgOutput::Filters(filters) => MsgInput::UpdateFilters(filters),
+                super::filter::MsgOutput::Move(task, filter) => MsgInput::Move(task, filter),
+                super::filter::MsgOutput::Reorder(changes) => MsgInput::Reorder(changes),
+            });
+
+        let model = Self {
+            tag: init,
+            filter,
+            filters: Vec::new(),
+        };
 
         let widgets = view_output!();
 
@@ -148,12 +99,34 @@ impl relm4::SimpleComponent for Model {
         use MsgInput::*;
 
         match msg {
+            Bulk(action, tasks) => {
+                sender.output(MsgOutput::Bulk(action, tasks)).ok();
+            }
             Complete(task) => {
                 sender.output(MsgOutput::Complete(task)).ok();
             }
+            Delete(task) => {
+                sender.output(MsgOutput::Delete(task)).ok();
+            }
+            Duplicate(task) => {
+                sender.output(MsgOutput::Duplicate(task)).ok();
+            }
             Edit(task) => {
                 sender.output(MsgOutput::Edit(task)).ok();
             }
+            Move(task, filter) => {
+                sender
+                    .output(MsgOutput::Move(
+                        task,
+                        self.tag,
+                        self.filters.clone(),
+                        filter,
+                    ))
+                    .ok();
+            }
+            Reorder(changes) => {
+                sender.output(MsgOutput::Reorder(changes)).ok();
+            }
             Update => {
                 self.update_tags();
                 self.update_tasks(&[]);
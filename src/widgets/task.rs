@@ -3,16 +3,24 @@ use gtk::prelude::*;
 #[derive(Debug)]
 pub enum MsgInput {
     Click,
+    Delete,
+    Duplicate,
     Toggle,
 }
 #[derive(Debug)]
 pub enum MsgOutput {
+    Bulk(crate::tasks::bulk::Action, Vec<crate::tasks::Task>),
     Complete(Box<crate::tasks::Task>),
+    Delete(Box<crate::tasks::Task>),
+    Duplicate(Box<crate::tasks::Task>),
     Edit(Box<crate::tasks::Task>),
+    Reorder(Vec<(crate::tasks::Task, crate::tasks::Task)>),
 }
 
 pub struct Model {
     task: crate::tasks::Task,
+    subject: String,
+    snippet: Option<String>,
     circle: relm4::Controller<crate::widgets::circle::Model>,
 }
 
@@ -34,7 +42,7 @@ impl Model {
 
 #[relm4::component(pub)]
 impl relm4::SimpleComponent for Model {
-    type Init = crate::tasks::Task;
+    type Init = crate::query::Hit;
     type Input = MsgInput;
     type Output = MsgOutput;
 
@@ -47,11 +55,22 @@ impl relm4::SimpleComponent for Model {
         use relm4::Component as _;
         use relm4::ComponentController as _;
 
+        let crate::query::Hit {
+            task,
+            highlights,
+            snippet,
+        } = init;
+
         let circle = crate::widgets::circle::Model::builder()
-            .launch(init.clone())
+            .launch(task.clone())
             .detach();
 
-        let model = Self { task: init, circle };
+        let model = Self {
+            subject: task.markup_subject(&highlights),
+            snippet: snippet.as_ref().map(crate::query::hit::Snippet::markup),
+            task,
+            circle,
+        };
 
         let widgets = view_output!();
 
@@ -71,6 +90,22 @@ impl relm4::SimpleComponent for Model {
         });
         root.add_controller(gesture);
 
+        let gesture = gtk::GestureClick::new();
+        gesture.set_button(gtk::gdk::BUTTON_SECONDARY);
+        let menu = widgets.menu.clone();
+        gesture.connect_pressed(move |_, _, _, _| menu.popup());
+        root.add_controller(gesture);
+
+        let drag = gtk::DragSource::new();
+        drag.set_actions(gtk::gdk::DragAction::MOVE);
+        let task = model.task.clone();
+        drag.connect_prepare(move |_, _, _| {
+            let value = gtk::glib::BoxedAnyObject::new(task.clone()).to_value();
+
+            Some(gtk::gdk::ContentProvider::for_value(&value))
+        });
+        root.add_controller(drag);
+
         if !model.task.priority.is_lowest() {
             let priority = (b'a' + u8::from(model.task.priority.clone())) as char;
             root.add_css_class(&format!("pri_{priority}"));
@@ -87,6 +122,12 @@ impl relm4::SimpleComponent for Model {
             MsgInput::Click => sender
                 .output(MsgOutput::Edit(Box::new(self.task.clone())))
                 .ok(),
+            MsgInput::Delete => sender
+                .output(MsgOutput::Delete(Box::new(self.task.clone())))
+                .ok(),
+            MsgInput::Duplicate => sender
+                .output(MsgOutput::Duplicate(Box::new(self.task.clone())))
+                .ok(),
         };
     }
 
@@ -110,14 +151,26 @@ impl relm4::SimpleComponent for Model {
                         connect_toggled => MsgInput::Toggle,
                     },
                     gtk::Label {
-                        set_markup: model.task.markup_subject().as_str(),
+                        set_markup: &model.subject,
                         set_xalign: 0.,
                     },
                 },
+                gtk::Label {
+                    add_css_class: "snippet",
+                    set_ellipsize: gtk::pango::EllipsizeMode::End,
+                    set_markup?: &model.snippet,
+                    set_visible: model.snippet.is_some(),
+                    set_xalign: 0.,
+                },
                 gtk::Box {
                     set_orientation: gtk::Orientation::Horizontal,
                     set_spacing: 5,
 
+                    gtk::Label {
+                        add_css_class: "workspace",
+                        set_text?: &model.task.workspace,
+                        set_visible: model.task.workspace.is_some(),
+                    },
                     gtk::MenuButton {
                         set_icon_name: "text-x-generic",
                         set_visible: model.task.has_note(),
@@ -166,6 +219,40 @@ impl relm4::SimpleComponent for Model {
                             set_visible: model.task.due_date.is_some(),
                         },
                     },
+                    #[name="menu"]
+                    gtk::MenuButton {
+                        add_css_class: "flat",
+                        set_icon_name: "view-more",
+                        set_tooltip_text: Some("Actions"),
+
+                        #[wrap(Some)]
+                        #[name="menu_popover"]
+                        set_popover = &gtk::Popover {
+                            gtk::Box {
+                                set_orientation: gtk::Orientation::Vertical,
+
+                                gtk::Button {
+                                    add_css_class: "flat",
+                                    set_label: "Duplicate",
+
+                                    connect_clicked[sender, menu_popover] => move |_| {
+                                        menu_popover.popdown();
+                                        sender.input(MsgInput::Duplicate);
+                                    },
+                                },
+                                gtk::Button {
+                                    add_css_class: "flat",
+                                    add_css_class: "destructive-action",
+                                    set_label: "Delete",
+
+                                    connect_clicked[sender, menu_popover] => move |_| {
+                                        menu_popover.popdown();
+                                        sender.input(MsgInput::Delete);
+                                    },
+                                },
+                            },
+                        },
+                    },
                 },
             },
             append: model.circle.widget(),
@@ -1,12 +1,24 @@
 use gtk::prelude::*;
 
+use crate::application::Shortcut;
+
 #[derive(Debug)]
 pub enum Msg {
+    Bulk(crate::tasks::bulk::Action),
+    Drop(Box<crate::tasks::Task>, f64),
+    Flat(Vec<crate::query::Hit>),
+    Key(Shortcut),
+    Page(String),
+    Ranked(Vec<crate::query::Hit>),
     Update(Vec<crate::tasks::Task>),
 }
 
 pub struct Model {
-    children: Vec<relm4::Controller<super::task::Model>>,
+    bulk: relm4::Controller<super::bulk::Model>,
+    children: Vec<(u64, relm4::Controller<super::task::Model>)>,
+    groups: Vec<(String, gtk::Expander, relm4::Controller<Model>)>,
+    page: String,
+    tasks: Vec<crate::tasks::Task>,
 }
 
 impl Model {
@@ -14,61 +26,358 @@ impl Model {
         &mut self,
         widgets: &ModelWidgets,
         sender: relm4::ComponentSender<Self>,
-        tasks: &[crate::tasks::Task],
+        tasks: Vec<crate::query::Hit>,
+        sort: bool,
+        group: bool,
     ) {
-        use relm4::Component as _;
-        use relm4::ComponentController as _;
-
-        self.clear(widgets);
-
         if tasks.is_empty() {
+            self.clear(widgets);
+            self.clear_groups(widgets);
             widgets.label.set_visible(true);
             widgets.list_box.set_visible(false);
             return;
         }
 
         widgets.label.set_visible(false);
-        widgets.list_box.set_visible(true);
 
-        let mut sorted_tasks = tasks.to_owned();
-        sorted_tasks.sort();
-        sorted_tasks.reverse();
+        let preferences = crate::application::preferences();
+
+        let mut sorted_tasks = tasks;
+        if sort {
+            crate::query::sort::sort(&mut sorted_tasks, preferences.sort(&self.page));
+        }
+
+        let group = if group {
+            preferences.group(&self.page)
+        } else {
+            crate::query::group::Group::None
+        };
+
+        if group == crate::query::group::Group::None {
+            self.clear_groups(widgets);
+            widgets.list_box.set_visible(true);
+            self.update_rows(widgets, sender, &sorted_tasks);
+            self.tasks = sorted_tasks.into_iter().map(|x| x.task).collect();
+        } else {
+            self.clear(widgets);
+            widgets.list_box.set_visible(false);
+
+            let groups = crate::query::group::group(&sorted_tasks, group, crate::date::today());
+            self.update_groups(widgets, sender, groups);
+        }
+    }
+
+    fn update_rows(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: relm4::ComponentSender<Self>,
+        sorted_tasks: &[crate::query::Hit],
+    ) {
+        use relm4::Component as _;
+        use relm4::ComponentController as _;
+
+        let mut old = std::collections::HashMap::<_, Vec<_>>::new();
+
+        for (key, child) in self.children.drain(..) {
+            old.entry(key).or_default().push(child);
+        }
+
+        for hit in sorted_tasks {
+            let key = Self::key(hit);
+
+            let child = match old.get_mut(&key).and_then(Vec::pop) {
+                Some(child) => child,
+                None => super::task::Model::builder()
+                    .launch(hit.clone())
+                    .forward(sender.output_sender(), std::convert::identity),
+            };
+
+            self.children.push((key, child));
+        }
+
+        for child in old.into_values().flatten() {
+            if let Some(row) = child.widget().parent() {
+                widgets.list_box.remove(&row);
+            }
+        }
+
+        for (position, (_, child)) in self.children.iter().enumerate() {
+            let position = position as i32;
+            let row = child
+                .widget()
+                .parent()
+                .and_then(|x| x.downcast::<gtk::ListBoxRow>().ok());
+
+            match row {
+                Some(row) if row.index() == position => (),
+                Some(row) => {
+                    widgets.list_box.remove(&row);
+                    widgets.list_box.insert(&row, position);
+                }
+                None => widgets.list_box.insert(child.widget(), position),
+            }
+        }
+    }
+
+    fn update_groups(
+        &mut self,
+        widgets: &ModelWidgets,
+        sender: relm4::ComponentSender<Self>,
+        groups: Vec<(String, Vec<crate::query::Hit>)>,
+    ) {
+        use relm4::Component as _;
+        use relm4::ComponentController as _;
+
+        let mut old = self
+            .groups
+            .drain(..)
+            .map(|(label, expander, child)| (label, (expander, child)))
+            .collect::<std::collections::HashMap<_, _>>();
+        let mut previous = None::<gtk::Expander>;
+
+        for (label, tasks) in groups {
+            let (expander, child) = old.remove(&label).unwrap_or_else(|| {
+                let child = Self::builder()
+                    .launch(self.page.clone())
+                    .forward(sender.output_sender(), std::convert::identity);
+                child.widget().set_vscrollbar_policy(gtk::PolicyType::Never);
+
+                let expander = gtk::Expander::new(None);
+                expander.set_expanded(true);
+                expander.set_child(Some(child.widget()));
+
+                (expander, child)
+            });
+
+            expander.set_label(Some(&format!("{label} ({})", tasks.len())));
+            child.emit(Msg::Flat(tasks));
+
+            if expander.parent().is_some() {
+                widgets
+                    .groups
+                    .reorder_child_after(&expander, previous.as_ref());
+            } else {
+                widgets
+                    .groups
+                    .insert_child_after(&expander, previous.as_ref());
+            }
+
+            previous = Some(expander.clone());
+            self.groups.push((label, expander, child));
+        }
+
+        for (expander, _) in old.into_values() {
+            widgets.groups.remove(&expander);
+        }
+    }
+
+    fn selected(&self, widgets: &ModelWidgets) -> Vec<crate::tasks::Task> {
+        let mut rows = widgets.list_box.selected_rows();
+
+        if rows.is_empty() {
+            rows.extend(
+                widgets
+                    .list_box
+                    .focus_child()
+                    .and_then(|x| x.downcast::<gtk::ListBoxRow>().ok()),
+            );
+        }
+
+        rows.iter()
+            .filter_map(|x| self.tasks.get(x.index() as usize))
+            .cloned()
+            .collect()
+    }
+
+    fn press(&self, widgets: &ModelWidgets, sender: relm4::ComponentSender<Self>, key: Shortcut) {
+        use crate::tasks::bulk::Action;
+        use crate::widgets::task::MsgOutput;
+
+        let count = match key {
+            Shortcut::Next => 1,
+            Shortcut::Previous => -1,
+            _ => 0,
+        };
+
+        if count != 0 {
+            widgets
+                .list_box
+                .emit_move_cursor(gtk::MovementStep::DisplayLines, count, false, false);
+            return;
+        }
+
+        let tasks = self.selected(widgets);
+
+        let Some(task) = tasks.first().cloned() else {
+            return;
+        };
+
+        let output = match key {
+            Shortcut::Complete if tasks.len() == 1 => MsgOutput::Complete(Box::new(task)),
+            Shortcut::Complete => MsgOutput::Bulk(Action::Complete, tasks),
+            Shortcut::Defer => {
+                let tomorrow = crate::date::today().succ_opt();
+
+                MsgOutput::Bulk(Action::Defer(tomorrow), tasks)
+            }
+            Shortcut::Delete if tasks.len() == 1 => MsgOutput::Delete(Box::new(task)),
+            Shortcut::Delete => MsgOutput::Bulk(Action::Delete, tasks),
+            Shortcut::Edit => MsgOutput::Edit(Box::new(task)),
+            Shortcut::Flag => MsgOutput::Bulk(Action::Flag(!task.flagged), tasks),
+            Shortcut::Lower => MsgOutput::Bulk(Action::ShiftPriority(1), tasks),
+            Shortcut::Raise => MsgOutput::Bulk(Action::ShiftPriority(-1), tasks),
+            _ => return,
+        };
+
+        sender.output(output).ok();
+    }
+
+    fn bulk(
+        &self,
+        widgets: &ModelWidgets,
+        sender: relm4::ComponentSender<Self>,
+        action: crate::tasks::bulk::Action,
+    ) {
+        let tasks = self.selected(widgets);
+
+        widgets.list_box.unselect_all();
+
+        if !tasks.is_empty() {
+            sender
+                .output(crate::widgets::task::MsgOutput::Bulk(action, tasks))
+                .ok();
+        }
+    }
+
+    fn drop(
+        &self,
+        widgets: &ModelWidgets,
+        sender: relm4::ComponentSender<Self>,
+        task: &crate::tasks::Task,
+        y: f64,
+    ) {
+        let Some(from) = self.tasks.iter().position(|x| x.is(task)) else {
+            return;
+        };
 
-        for task in &sorted_tasks {
-            let child = super::task::Model::builder()
-                .launch(task.clone())
-                .forward(sender.output_sender(), std::convert::identity);
+        let to = widgets
+            .list_box
+            .row_at_y(y as i32)
+            .map(|x| x.index() as usize)
+            .unwrap_or(self.tasks.len());
 
-            widgets.list_box.append(child.widget());
+        let changes = crate::query::sort::reorder(&self.tasks, from, to);
 
-            self.children.push(child);
+        if !changes.is_empty() {
+            sender
+                .output(crate::widgets::task::MsgOutput::Reorder(changes))
+                .ok();
         }
     }
 
+    fn clear_groups(&mut self, widgets: &ModelWidgets) {
+        for (_, expander, _) in self.groups.drain(..) {
+            widgets.groups.remove(&expander);
+        }
+    }
+
+    fn key(hit: &crate::query::Hit) -> u64 {
+        use std::hash::{Hash as _, Hasher as _};
+
+        let task = &hit.task;
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        task.fingerprint().hash(&mut hasher);
+        task.archived.hash(&mut hasher);
+        task.workspace.hash(&mut hasher);
+        task.note.content().hash(&mut hasher);
+        hit.highlights.hash(&mut hasher);
+        hit.snippet.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
     fn clear(&mut self, widgets: &ModelWidgets) {
         widgets.list_box.remove_all();
         self.children = Vec::new();
+        self.tasks = Vec::new();
     }
 }
 
 #[relm4::component(pub)]
 impl relm4::Component for Model {
     type CommandOutput = ();
-    type Init = ();
+    type Init = String;
     type Input = Msg;
     type Output = crate::widgets::task::MsgOutput;
 
     fn init(
-        _: Self::Init,
+        init: Self::Init,
         root: Self::Root,
-        _sender: relm4::ComponentSender<Self>,
+        sender: relm4::ComponentSender<Self>,
     ) -> relm4::ComponentParts<Self> {
+        use relm4::ComponentController as _;
+
+        let bulk =
+            super::bulk::Model::builder()
+                .launch(())
+                .forward(sender.input_sender(), |output| match output {
+                    super::bulk::MsgOutput::Apply(action) => Msg::Bulk(action),
+                });
+        bulk.widget().set_visible(false);
+
         let model = Self {
+            bulk,
             children: Vec::new(),
+            groups: Vec::new(),
+            page: init,
+            tasks: Vec::new(),
         };
 
         let widgets = view_output!();
 
+        let drop = gtk::DropTarget::new(
+            gtk::glib::BoxedAnyObject::static_type(),
+            gtk::gdk::DragAction::MOVE,
+        );
+        let drop_sender = sender.clone();
+        drop.connect_drop(move |_, value, _, y| {
+            let Ok(object) = value.get::<gtk::glib::BoxedAnyObject>() else {
+                return false;
+            };
+
+            let task = object.borrow::<crate::tasks::Task>().clone();
+            drop_sender.input(Msg::Drop(Box::new(task), y));
+
+            true
+        });
+        widgets.list_box.add_controller(drop);
+
+        let shortcuts = gtk::ShortcutController::new();
+
+        for (shortcut, trigger) in crate::application::keymap().bindings() {
+            if shortcut.is_global() {
+                continue;
+            }
+
+            let Some(trigger) = gtk::ShortcutTrigger::parse_string(trigger) else {
+                log::warn!("Invalid shortcut '{trigger}' for {}", shortcut.name());
+                continue;
+            };
+
+            let callback = gtk::CallbackAction::new(gtk::glib::clone!(
+                #[strong]
+                sender,
+                move |_, _| {
+                    sender.input(Msg::Key(shortcut));
+                    gtk::glib::Propagation::Stop
+                }
+            ));
+
+            shortcuts.add_shortcut(gtk::Shortcut::new(Some(trigger), Some(callback)));
+        }
+
+        widgets.list_box.add_controller(shortcuts);
+
         relm4::ComponentParts { model, widgets }
     }
 
@@ -82,17 +391,40 @@ impl relm4::Component for Model {
         use Msg::*;
 
         match msg {
-            Update(tasks) => self.update_tasks(widgets, sender, &tasks),
+            Bulk(action) => self.bulk(widgets, sender, action),
+            Drop(task, y) => self.drop(widgets, sender, &task, y),
+            Flat(tasks) => self.update_tasks(widgets, sender, tasks, false, false),
+            Key(key) => self.press(widgets, sender, key),
+            Page(page) => self.page = page,
+            Ranked(tasks) => self.update_tasks(widgets, sender, tasks, false, true),
+            Update(tasks) => {
+                let tasks = tasks.into_iter().map(crate::query::Hit::from).collect();
+
+                self.update_tasks(widgets, sender, tasks, true, true);
+            }
         }
     }
 
     view! {
         gtk::ScrolledWindow {
             gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+
+                append: model.bulk.widget(),
                 #[name = "list_box"]
                 gtk::ListBox {
                     set_hexpand: true,
+                    set_selection_mode: gtk::SelectionMode::Multiple,
                     set_vexpand: true,
+
+                    connect_selected_rows_changed[bulk = model.bulk.widget().clone()] => move |this| {
+                        bulk.set_visible(this.selected_rows().len() > 1);
+                    },
+                },
+                #[name = "groups"]
+                gtk::Box {
+                    set_hexpand: true,
+                    set_orientation: gtk::Orientation::Vertical,
                 },
                 #[name = "label"]
                 gtk::Label {